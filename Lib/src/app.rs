@@ -1,7 +1,11 @@
 use crate::{
-    AppEnvironment, Logging, ModifyView, Puppeteer, PuppeteerError, PuppeteerResult, StaticAsset,
-    UiEvent, UiPaint,
+    route_from_ipc, AppEnvironment, BindingChange, Bindings, Clipboard, ClipboardRequest,
+    ComponentMessage, Components, ContrastSeverity, CspViolation, DevMode, ListRequest,
+    Localization, Logging, ModalCSS, ModalResponse, Modals, ModifyView, Puppeteer, PuppeteerError,
+    PuppeteerResult, Router, Shortcuts, StaticAsset, Theme, ToastCSS, UiEvent, UiPaint, VDom,
+    VirtualLists, DEVTOOLS_IPC_MESSAGE, SHORTCUT_IPC_PREFIX,
 };
+use std::sync::Arc;
 use tao::{
     dpi::PhysicalSize,
     event::{Event, StartCause, WindowEvent},
@@ -33,6 +37,48 @@ pub struct ActiveAppEnv {
     pub available_monitors: Vec<MonitorHandle>,
     /// List all the fonts that were loaded by the app
     pub fonts: &'static [StaticAsset],
+    /// The clipboard shared by the event handlers and JavaScript.
+    /// This defaults to the system clipboard and falls back to an in-memory clipboard
+    /// if the system clipboard is unavailable.
+    pub clipboard: Clipboard,
+    /// The trees rendered using the virtual DOM, shared by the event handlers
+    pub vdom: VDom,
+    /// The [crate::Component] instances that are mounted
+    pub components: Components,
+    /// The [Router] used to change the page painted in the app
+    pub router: Router,
+    /// The values of the fields bound to elements
+    pub bindings: Bindings,
+    /// The translations and the current locale, detected from the operating system
+    pub localization: Localization,
+    /// The modals the event handlers can wait on
+    pub modals: Modals,
+    /// The data sources of the virtual lists
+    pub virtual_lists: VirtualLists,
+}
+
+impl ActiveAppEnv {
+    /// Initialize the environment before the window is created.
+    /// The monitors are detected when the window is created and the clipboard
+    /// is an in-memory clipboard
+    pub fn new(app_name: &'static str) -> Self {
+        ActiveAppEnv {
+            app_name,
+            env: AppEnvironment::init(),
+            primary_monitor: Option::default(),
+            current_monitor: Option::default(),
+            available_monitors: Vec::default(),
+            fonts: &[],
+            clipboard: Clipboard::memory(),
+            vdom: VDom::new(),
+            components: Components::new(),
+            router: Router::new(),
+            bindings: Bindings::new(),
+            localization: Localization::default(),
+            modals: Modals::new(),
+            virtual_lists: VirtualLists::new(),
+        }
+    }
 }
 
 /// This struct us used to build your app
@@ -41,6 +87,8 @@ pub struct PuppeteerApp<T: Puppeteer + 'static + Send + Sync> {
     pub env: ActiveAppEnv,
     event_loop: EventLoop<UiEvent<T>>,
     proxy: EventLoopProxy<UiEvent<T>>,
+    shortcuts: Arc<Shortcuts<T>>,
+    dev_mode: DevMode,
 }
 
 impl<T> PuppeteerApp<T>
@@ -55,27 +103,68 @@ where
         let proxy = event_loop.create_proxy();
         Logging::new(app_name).log("INITIALIZED EVENT_LOOP PROXY");
 
+        let clipboard = Clipboard::system().unwrap_or_else(|error| {
+            Logging::new(app_name).with_level(Level::WARN).log(&format!(
+                "SYSTEM CLIPBOARD UNAVAILABLE, USING MEMORY CLIPBOARD: {error}"
+            ));
+
+            Clipboard::memory()
+        });
+
+        let env = ActiveAppEnv {
+            clipboard,
+            ..ActiveAppEnv::new(app_name)
+        };
+
         PuppeteerApp {
             event_loop,
             proxy,
-            env: ActiveAppEnv {
-                app_name,
-                env: AppEnvironment::init(),
-                primary_monitor: Option::default(),
-                current_monitor: Option::default(),
-                available_monitors: Vec::default(),
-                fonts: &[StaticAsset {
-                    name: "",
-                    bytes: &[0u8],
-                }],
-            },
+            shortcuts: Arc::new(Shortcuts::new(&env.env)),
+            dev_mode: DevMode::new(),
+            env,
         }
     }
 
-    /// Load fonts directory
+    /// Use a custom [Clipboard] like an in-memory clipboard instead of the system clipboard
+    pub fn with_clipboard(mut self, clipboard: Clipboard) -> Self {
+        self.env.clipboard = clipboard;
+
+        self
+    }
+
+    /// Add the keyboard shortcuts. A single keydown dispatcher script is added to the [crate::Shell]
+    /// and the custom events of the shortcuts are sent to `Puppeteer::event_handler()`
+    pub fn with_shortcuts(mut self, shortcuts: Shortcuts<T>) -> Self {
+        self.shortcuts = Arc::new(shortcuts);
+
+        self
+    }
+
+    /// Configure the developer mode. By default the developer mode is only enabled
+    /// by the `PUPPETEER_DEV` environment variable and no files are watched
+    pub fn with_dev_mode(mut self, dev_mode: DevMode) -> Self {
+        self.dev_mode = dev_mode;
+
+        self
+    }
+
+    /// Use a [Router] to change the pages of the app
+    pub fn with_router(mut self, router: Router) -> Self {
+        self.env.router = router;
+
+        self
+    }
+
+    /// Use a [Localization] with the translations of the app
+    pub fn with_localization(mut self, localization: Localization) -> Self {
+        self.env.localization = localization;
+
+        self
+    }
+
+    /// Add the fonts to the [crate::Shell] when the app starts, see [crate::Shell::add_fonts]
     pub fn with_fonts(mut self, fonts: &'static [StaticAsset]) -> Self {
         self.env.fonts = fonts;
-        T::shell().add_fonts(&self.env);
 
         self
     }
@@ -84,8 +173,37 @@ where
     /// This method is async runtime agnostic and can be used with any
     /// Rust async runtime that respects `std::future::Future`
     pub async fn start(mut self) -> PuppeteerResult<()> {
-        let (webview, window) =
-            PuppeteerApp::<T>::create_webview(&self.event_loop, self.proxy.clone(), &mut self.env)?;
+        let (webview, window) = PuppeteerApp::<T>::create_webview(
+            &self.event_loop,
+            self.proxy.clone(),
+            &mut self.env,
+            self.shortcuts.clone(),
+            &self.dev_mode,
+        )?;
+
+        let modal_proxy = std::sync::Mutex::new(self.proxy.clone());
+        self.env.modals.set_painter(move |view| {
+            modal_proxy
+                .lock()
+                .map(|proxy| proxy.send_event(UiEvent::Paint(view)).is_ok())
+                .unwrap_or_default()
+        });
+        self.env
+            .modals
+            .set_localization(self.env.localization.clone());
+
+        if self.dev_mode.is_enabled() {
+            Logging::new(self.env.app_name).log("DEVELOPER MODE ENABLED");
+
+            PuppeteerApp::<T>::watch_files(
+                self.dev_mode.clone(),
+                self.proxy.clone(),
+                self.env.app_name,
+            );
+        }
+
+        // The theme set by the app, `Theme::System` follows the `ThemeChanged` events
+        let mut theme = T::shell().theme();
 
         self.event_loop
             .run(move |event, _event_loop, control_flow| {
@@ -109,6 +227,16 @@ where
                     } => {
                         *control_flow = ControlFlow::Exit;
                     }
+                    Event::WindowEvent {
+                        event: WindowEvent::ThemeChanged(system_theme),
+                        ..
+                    } if theme == Theme::System => {
+                        PuppeteerApp::<T>::eval_script_exit_on_error(
+                            self.env.app_name,
+                            &webview,
+                            &ModifyView::SetTheme(Theme::from(system_theme)),
+                        );
+                    }
                     Event::UserEvent(update_view) => match update_view {
                         UiEvent::Init => {
                             PuppeteerApp::<T>::send_init_event(
@@ -156,6 +284,39 @@ where
                                 custom_event,
                             );
                         }
+                        UiEvent::ToggleDevtools => {
+                            PuppeteerApp::<T>::toggle_devtools(self.env.app_name, &webview)
+                        }
+                        UiEvent::HotReload(reload) => {
+                            Logging::new(self.env.app_name)
+                                .log(&format!("HOT RELOADING `{}`", reload.path));
+
+                            if !reload.repaints_view() {
+                                PuppeteerApp::<T>::eval_script_exit_on_error(
+                                    self.env.app_name,
+                                    &webview,
+                                    &reload.to_script().as_str(),
+                                );
+                            } else if self.env.router.is_empty() {
+                                Logging::new(self.env.app_name)
+                                    .with_level(Level::WARN)
+                                    .log("RELOADING A VIEW FILE REQUIRES A ROUTER");
+                            } else {
+                                let app_env = self.env.clone();
+                                let local_proxy = self.proxy.clone();
+
+                                smol::spawn(async move {
+                                    let outcome = app_env.router.reload(&app_env).await;
+
+                                    PuppeteerApp::<T>::send_event(
+                                        local_proxy,
+                                        app_env,
+                                        outcome.map_or_else(UiEvent::Error, UiEvent::Paint),
+                                    );
+                                })
+                                .detach();
+                            }
+                        }
                         UiEvent::Paint(paint_data) => match paint_data {
                             ModifyView::ComputeWithIdData { func, .. } => {
                                 PuppeteerApp::<T>::callback_script_by_id(
@@ -176,12 +337,171 @@ where
                                 )
                             }
                             ModifyView::Skip => (),
+                            ModifyView::SetTheme(new_theme) => {
+                                theme = new_theme;
+
+                                PuppeteerApp::<T>::eval_script_exit_on_error(
+                                    self.env.app_name,
+                                    &webview,
+                                    &ModifyView::SetTheme(
+                                        theme.resolve(Theme::from(window.theme())),
+                                    ),
+                                );
+                            }
+                            ModifyView::Navigate(_) | ModifyView::Back | ModifyView::Forward => {
+                                let app_env = self.env.clone();
+                                let local_proxy = self.proxy.clone();
+
+                                smol::spawn(async move {
+                                    let outcome = match paint_data {
+                                        ModifyView::Navigate(path) => {
+                                            app_env.router.navigate(&path, &app_env).await
+                                        }
+                                        ModifyView::Back => app_env.router.back(&app_env).await,
+                                        _ => app_env.router.forward(&app_env).await,
+                                    };
+
+                                    PuppeteerApp::<T>::send_event(
+                                        local_proxy,
+                                        app_env,
+                                        outcome.map_or_else(UiEvent::Error, UiEvent::Paint),
+                                    );
+                                })
+                                .detach();
+                            }
+                            ModifyView::SetLocale(locale) => {
+                                if let Err(error) = self.env.localization.set_locale(&locale) {
+                                    PuppeteerApp::<T>::send_event(
+                                        self.proxy.clone(),
+                                        self.env.clone(),
+                                        UiEvent::Error(error),
+                                    );
+
+                                    return;
+                                }
+
+                                PuppeteerApp::<T>::eval_script_exit_on_error(
+                                    self.env.app_name,
+                                    &webview,
+                                    &self.env.localization.document_script().as_str(),
+                                );
+
+                                let app_env = self.env.clone();
+                                let local_proxy = self.proxy.clone();
+
+                                smol::spawn(async move {
+                                    let outcome = if app_env.router.is_empty() {
+                                        Ok(T::on_locale_change(&app_env).await)
+                                    } else {
+                                        app_env.router.reload(&app_env).await
+                                    };
+
+                                    PuppeteerApp::<T>::send_event(
+                                        local_proxy,
+                                        app_env,
+                                        outcome.map_or_else(UiEvent::Error, UiEvent::Paint),
+                                    );
+                                })
+                                .detach();
+                            }
+                            ModifyView::SetTitle(ref title) => {
+                                window.set_title(title);
+
+                                PuppeteerApp::<T>::eval_script_exit_on_error(
+                                    self.env.app_name,
+                                    &webview,
+                                    &paint_data,
+                                )
+                            }
+                            ModifyView::Batch(_) => {
+                                let (paint_data, app_operations) = paint_data.take_app_operations();
+                                let callbacks = paint_data.batch_callbacks();
+
+                                if callbacks.is_empty() {
+                                    PuppeteerApp::<T>::eval_script_exit_on_error(
+                                        self.env.app_name,
+                                        &webview,
+                                        &paint_data,
+                                    )
+                                } else {
+                                    PuppeteerApp::<T>::callback_script_batch(
+                                        self.env.app_name,
+                                        &webview,
+                                        self.proxy.clone(),
+                                        paint_data,
+                                        callbacks,
+                                    )
+                                }
+
+                                app_operations.into_iter().for_each(|view| {
+                                    PuppeteerApp::<T>::send_event(
+                                        self.proxy.clone(),
+                                        self.env.clone(),
+                                        UiEvent::Paint(view),
+                                    )
+                                });
+                            }
                             _ => PuppeteerApp::<T>::eval_script_exit_on_error(
                                 self.env.app_name,
                                 &webview,
                                 &paint_data,
                             ),
                         },
+                        UiEvent::Binding(change) => {
+                            self.env.bindings.update(&change);
+
+                            if let Some(event) = T::on_binding_change(&change) {
+                                PuppeteerApp::<T>::send_event_from_future(
+                                    self.proxy.clone(),
+                                    self.env.clone(),
+                                    event,
+                                );
+                            }
+                        }
+                        UiEvent::Modal(response) => self.env.modals.resolve(response),
+                        UiEvent::VirtualList(request) => {
+                            let app_env = self.env.clone();
+                            let local_proxy = self.proxy.clone();
+
+                            smol::spawn(async move {
+                                let event = app_env
+                                    .virtual_lists
+                                    .rows(request, &app_env)
+                                    .await
+                                    .map_or_else(UiEvent::Error, UiEvent::Paint);
+
+                                PuppeteerApp::<T>::send_event(local_proxy, app_env, event);
+                            })
+                            .detach();
+                        }
+                        UiEvent::Component(message) => {
+                            let app_env = self.env.clone();
+                            let local_proxy = self.proxy.clone();
+
+                            smol::spawn(async move {
+                                let event = app_env
+                                    .components
+                                    .dispatch(message, &app_env)
+                                    .await
+                                    .map_or_else(UiEvent::Error, UiEvent::Paint);
+
+                                PuppeteerApp::<T>::send_event(local_proxy, app_env, event);
+                            })
+                            .detach();
+                        }
+                        UiEvent::Clipboard(request) => match request.perform(&self.env.clipboard) {
+                            Ok(Some(content)) => PuppeteerApp::<T>::eval_script_exit_on_error(
+                                self.env.app_name,
+                                &webview,
+                                &content.to_dom_event_script().as_str(),
+                            ),
+                            Ok(None) => (),
+                            Err(error) => PuppeteerApp::<T>::send_event(
+                                self.proxy.clone(),
+                                self.env.clone(),
+                                UiEvent::Error(error),
+                            ),
+                        },
                     },
                     _ => (),
                 }
@@ -225,6 +545,8 @@ where
         event_loop: &EventLoopWindowTarget<UiEvent<T>>,
         proxy: EventLoopProxy<UiEvent<T>>,
         app_env: &mut ActiveAppEnv,
+        shortcuts: Arc<Shortcuts<T>>,
+        dev_mode: &DevMode,
     ) -> PuppeteerResult<(WebView, Window)> {
         let window = WindowBuilder::new()
             .with_title(app_env.app_name)
@@ -255,11 +577,63 @@ where
         app_env.primary_monitor = primary_monitor;
         app_env.current_monitor = current_monitor;
 
-        let handler = PuppeteerApp::<T>::handler(proxy, app_env.clone());
+        let mut shell = T::shell().with_system_theme(Theme::from(window.theme()));
+
+        // The title of the document and the title of the window are kept in sync
+        match shell.title() {
+            Some(title) => window.set_title(title),
+            None => shell = shell.with_title(&window.title()),
+        }
+
+        for (theme, palette) in [("LIGHT", shell.palette()), ("DARK", shell.dark_palette())] {
+            palette.audit_contrast().iter().for_each(|issue| {
+                let level = match issue.severity {
+                    ContrastSeverity::Warning => Level::WARN,
+                    ContrastSeverity::Error => Level::ERROR,
+                };
+
+                Logging::new(app_env.app_name)
+                    .with_level(level)
+                    .log(&format!("{theme} PALETTE: {issue}"));
+            });
+        }
+
+        shell = shell.add_fonts(app_env)?;
+
+        if shell.language().is_none() {
+            shell = shell.with_language(
+                &app_env.localization.locale(),
+                app_env.localization.direction(),
+            );
+        }
+
+        shell = shell.add_styles([ModalCSS::new().build(), ToastCSS::new().build()]);
+
+        if !shortcuts.is_empty() {
+            shell = shell.add_script(shortcuts.script().into());
+        }
+
+        if dev_mode.is_enabled() {
+            shell = dev_mode.name_styles(shell.add_script(DevMode::script().into()));
+        }
+
+        if !app_env.router.is_empty() {
+            shell = shell.add_script(Router::script(&app_env.env).into());
+        }
 
-        let devtools_enabled = cfg!(debug_assertions);
+        let csp_nonce = shell
+            .content_security_policy()
+            .map(|policy| policy.nonce().to_owned())
+            .unwrap_or_default();
+        let handler = PuppeteerApp::<T>::handler(
+            proxy,
+            app_env.clone(),
+            shortcuts,
+            dev_mode.is_enabled(),
+            csp_nonce,
+        );
 
-        let shell = T::shell();
+        let devtools_enabled = cfg!(debug_assertions) || dev_mode.is_enabled();
 
         #[cfg(any(
             target_os = "windows",
@@ -284,8 +658,11 @@ where
             WebViewBuilder::new_gtk(vbox)
         };
 
+        let (html, size_report) = shell.to_html_with_size_report();
+        Logging::new(app_env.app_name).log(&format!("SHELL DOCUMENT SIZE: {size_report:?}"));
+
         let webview = webview_builder
-            .with_html(shell.to_html())?
+            .with_html(html)?
             .with_devtools(devtools_enabled)
             .with_ipc_handler(handler)
             .build()?;
@@ -296,6 +673,9 @@ where
     fn handler(
         proxy: EventLoopProxy<UiEvent<T>>,
         app_env: ActiveAppEnv,
+        shortcuts: Arc<Shortcuts<T>>,
+        dev_mode_enabled: bool,
+        csp_nonce: String,
     ) -> Box<dyn Fn(String) + 'static> {
         let outcome = move |req: String| match req.as_str() {
             "minimize" => PuppeteerApp::<T>::proxy_error_handler(
@@ -315,19 +695,76 @@ where
                 proxy.send_event(UiEvent::Close),
                 app_env.app_name,
             ),
+            DEVTOOLS_IPC_MESSAGE if dev_mode_enabled => PuppeteerApp::<T>::proxy_error_handler(
+                proxy.send_event(UiEvent::ToggleDevtools),
+                app_env.app_name,
+            ),
             _ => {
-                let req_parse = T::parse(&req);
-
-                PuppeteerApp::<T>::proxy_error_handler(
-                    proxy.send_event(UiEvent::Custom(req_parse)),
-                    app_env.app_name,
-                )
+                let event = if let Some(request) = ClipboardRequest::from_ipc(&req) {
+                    request
+                        .and_then(|request| app_env.clipboard.authorize_ipc(request))
+                        .map_or_else(UiEvent::Error, UiEvent::Clipboard)
+                } else if let Some(navigation) = route_from_ipc(&req) {
+                    UiEvent::Paint(navigation)
+                } else if let Some(change) = BindingChange::from_ipc(&req) {
+                    UiEvent::Binding(change)
+                } else if let Some(request) = ListRequest::from_ipc(&req) {
+                    UiEvent::VirtualList(request)
+                } else if let Some(response) = ModalResponse::from_ipc(&req) {
+                    UiEvent::Modal(response)
+                } else if let Some(violation) = CspViolation::from_ipc(&req, &csp_nonce) {
+                    UiEvent::Error(violation.into())
+                } else if let Some(message) = ComponentMessage::from_ipc(&req) {
+                    UiEvent::Component(message)
+                } else if req.starts_with(SHORTCUT_IPC_PREFIX) {
+                    shortcuts.event(&req).map_or_else(
+                        || UiEvent::Error(PuppeteerError::InvalidShortcut(req.clone())),
+                        UiEvent::Custom,
+                    )
+                } else {
+                    UiEvent::Custom(T::parse(&req))
+                };
+
+                PuppeteerApp::<T>::proxy_error_handler(proxy.send_event(event), app_env.app_name)
             }
         };
 
         Box::new(outcome)
     }
 
+    fn watch_files(dev_mode: DevMode, proxy: EventLoopProxy<UiEvent<T>>, app_name: &'static str) {
+        smol::spawn(async move {
+            let outcome = dev_mode
+                .watch(|reload| Ok(proxy.send_event(UiEvent::HotReload(reload))?))
+                .await;
+
+            if let Err(error) = outcome {
+                Logging::new(app_name)
+                    .with_level(Level::ERROR)
+                    .log(error.to_string().as_str());
+            }
+        })
+        .detach()
+    }
+
+    #[cfg(any(debug_assertions, feature = "devtools"))]
+    fn toggle_devtools(app_name: &'static str, webview: &WebView) {
+        if webview.is_devtools_open() {
+            webview.close_devtools();
+            Logging::new(app_name).log("CLOSED DEVTOOLS");
+        } else {
+            webview.open_devtools();
+            Logging::new(app_name).log("OPENED DEVTOOLS");
+        }
+    }
+
+    #[cfg(not(any(debug_assertions, feature = "devtools")))]
+    fn toggle_devtools(app_name: &'static str, _webview: &WebView) {
+        Logging::new(app_name)
+            .with_level(Level::WARN)
+            .log("THE `devtools` FEATURE IS REQUIRED TO OPEN THE DEVTOOLS IN RELEASE BUILDS");
+    }
+
     fn proxy_error_handler(
         value: Result<(), EventLoopClosed<UiEvent<T>>>,
         log_filter_name: &'static str,
@@ -343,7 +780,11 @@ where
         }
     }
 
-    fn eval_script_exit_on_error(app_name: &'static str, webview: &WebView, content: &ModifyView) {
+    fn eval_script_exit_on_error(
+        app_name: &'static str,
+        webview: &WebView,
+        content: &impl UiPaint,
+    ) {
         match webview.evaluate_script(&content.to_html()) {
             Ok(_) => (),
             Err(error) => {
@@ -356,6 +797,44 @@ where
         }
     }
 
+    fn callback_script_batch(
+        app_name: &'static str,
+        webview: &WebView,
+        proxy: EventLoopProxy<UiEvent<T>>,
+        script: impl UiPaint,
+        callbacks: Vec<crate::JsCallback>,
+    ) {
+        let callback = move |value: String| {
+            let events = match crate::utils::split_json_array(&value) {
+                Some(results) if results.len() == callbacks.len() => results
+                    .into_iter()
+                    .zip(callbacks.iter())
+                    .map(|(result, callback_fn)| UiEvent::Paint(callback_fn(result)))
+                    .collect::<Vec<UiEvent<T>>>(),
+                _ => vec![UiEvent::Error(PuppeteerError::InvalidBatchResult(value))],
+            };
+
+            for event in events {
+                if proxy.send_event(event).is_err() {
+                    Logging::new(app_name)
+                        .with_level(Level::ERROR)
+                        .log(PuppeteerError::TaoEventLoopClosed.to_string().as_str());
+                }
+            }
+        };
+
+        match webview.evaluate_script_with_callback(&script.to_html(), callback) {
+            Ok(_) => (),
+            Err(error) => {
+                Logging::new(app_name)
+                    .with_level(Level::ERROR)
+                    .log(error.to_string().as_str());
+
+                std::process::exit(1);
+            }
+        }
+    }
+
     fn callback_script_by_id(
         app_name: &'static str,
         webview: &WebView,
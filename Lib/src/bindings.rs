@@ -0,0 +1,148 @@
+use crate::{ModifyView, VNode};
+use std::{
+    collections::BTreeMap,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+/// The prefix of the IPC messages sent when a bound element changes.
+/// The full message is `puppeteer:bind>{field}>{value}`
+pub const BINDING_IPC_PREFIX: &str = "puppeteer:bind>";
+
+/// The default number of milliseconds the [crate::PUPPETEER_RUNTIME_SCRIPT] waits
+/// after the last change of a bound element before sending its value
+pub const DEFAULT_BINDING_DEBOUNCE_MS: u32 = 150;
+
+/// The new value of a bound field sent by the webview
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct BindingChange {
+    /// The name of the field
+    pub field: String,
+    /// The new value. Checkboxes send `true` or `false`
+    pub value: String,
+}
+
+impl BindingChange {
+    /// Returns `None` if the IPC message is not a binding change
+    pub fn from_ipc(message: &str) -> Option<Self> {
+        let (field, value) = message.strip_prefix(BINDING_IPC_PREFIX)?.split_once('>')?;
+
+        Some(BindingChange {
+            field: field.to_owned(),
+            value: value.to_owned(),
+        })
+    }
+
+    /// Parse the value, for example into a number
+    pub fn parse<V: FromStr>(&self) -> Option<V> {
+        self.value.parse().ok()
+    }
+}
+
+/// The Rust side values of the bound fields, shared by the event handlers.
+///
+/// Elements are bound to a field using [VNode::bind] or the `data-pp-bind` attribute.
+/// When the user changes a bound `<input>`, `<textarea>` or `<select>`, the value is stored here
+/// and `Puppeteer::on_binding_change()` is called so the app can react with its own event.
+/// Values changed using [Bindings::set] are pushed into all the elements bound to the field,
+/// setting the value of form fields and the text of other elements.
+#[derive(Debug, Default, Clone)]
+pub struct Bindings {
+    values: Arc<Mutex<BTreeMap<String, String>>>,
+}
+
+impl Bindings {
+    /// Create the store without any value
+    pub fn new() -> Self {
+        Bindings::default()
+    }
+
+    /// The current value of the field
+    pub fn get(&self, field: &str) -> Option<String> {
+        self.lock().get(field).cloned()
+    }
+
+    /// The current value of the field parsed, for example into a number
+    pub fn get_as<V: FromStr>(&self, field: &str) -> Option<V> {
+        self.lock().get(field)?.parse().ok()
+    }
+
+    /// Store the value and return the [ModifyView] that pushes it into the bound elements
+    pub fn set(&self, field: &str, value: impl ToString) -> ModifyView {
+        let value = value.to_string();
+        self.lock().insert(field.to_owned(), value.clone());
+
+        ModifyView::SetBinding {
+            field: field.to_owned(),
+            value,
+        }
+    }
+
+    /// Store a value sent by the webview
+    pub fn update(&self, change: &BindingChange) {
+        self.lock()
+            .insert(change.field.clone(), change.value.clone());
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, String>> {
+        self.values
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl VNode {
+    /// Bind the element to the `field` of the [Bindings].
+    /// The field must not be empty or contain `>`, which separates the field from the value
+    /// in the IPC message, otherwise the element is not bound and a warning is logged
+    pub fn bind(self, field: &str) -> Self {
+        if field.is_empty() || field.contains('>') {
+            tracing::warn!("THE BINDING FIELD `{field}` IS EMPTY OR CONTAINS `>`");
+
+            return self;
+        }
+
+        self.attr("data-pp-bind", field)
+    }
+
+    /// Change how many milliseconds to wait after the last change of a bound element
+    /// before sending its value. Defaults to [DEFAULT_BINDING_DEBOUNCE_MS]
+    pub fn debounce(self, milliseconds: u32) -> Self {
+        self.attr("data-pp-debounce", &milliseconds.to_string())
+    }
+}
+
+#[cfg(test)]
+mod bindings_checks {
+    use super::*;
+    use crate::{Html, UiPaint};
+
+    #[test]
+    fn two_way() {
+        let change = BindingChange::from_ipc("puppeteer:bind>age>42").expect("Binding message");
+        let bindings = Bindings::new();
+        bindings.update(&change);
+
+        assert_eq!(Some(42u8), change.parse());
+        assert_eq!(Some(42u8), bindings.get_as("age"));
+        assert_eq!(None, BindingChange::from_ipc("puppeteer:bind>age"));
+
+        assert_eq!(
+            r#"window.puppeteer.setBinding("email","\u003cme@example.com\u003e");"#,
+            bindings.set("email", "<me@example.com>").to_html()
+        );
+        assert_eq!(Some("<me@example.com>".to_owned()), bindings.get("email"));
+
+        assert_eq!(
+            r#"<input data-pp-bind="email" data-pp-debounce="300">"#,
+            Html::input().bind("email").debounce(300).to_html()
+        );
+        assert_eq!("<input>", Html::input().bind("a>b").to_html());
+        assert_eq!("<input>", Html::input().bind("").to_html());
+        assert!(crate::PUPPETEER_RUNTIME_SCRIPT.contains(
+            &(String::from("const bindingDebounce = ")
+                + &DEFAULT_BINDING_DEBOUNCE_MS.to_string()
+                + ";")
+        ));
+    }
+}
@@ -0,0 +1,581 @@
+use crate::{utils::js_string, AssetFile, AssetProperties, PuppeteerError, PuppeteerResult};
+use base64ct::{Base64, Encoding};
+use bytes::BytesMut;
+use file_format::FileFormat;
+use std::{
+    borrow::Cow,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+/// The prefix of all IPC messages reserved for the clipboard.
+/// Messages starting with this prefix are never passed to `Puppeteer::parse()`
+pub const CLIPBOARD_IPC_PREFIX: &str = "puppeteer:clipboard:";
+
+/// The name of the DOM event dispatched on `document` when the clipboard has been read
+/// using an IPC message. The content read is in the `detail` field of the event as
+/// `{format: "text" | "html" | "image", content: "..."}`. Images are base64 data URIs.
+pub const CLIPBOARD_DOM_EVENT: &str = "puppeteer:clipboard";
+
+/// The name given to images read from the clipboard
+pub const CLIPBOARD_IMAGE_NAME: &str = "clipboard.png";
+
+/// The formats supported by the clipboard
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum ClipboardFormat {
+    /// Plain text
+    Text,
+    /// HTML markup
+    Html,
+    /// An image, read as PNG bytes
+    Image,
+}
+
+impl ClipboardFormat {
+    /// The name of the format as used in IPC messages and DOM events
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Html => "html",
+            Self::Image => "image",
+        }
+    }
+}
+
+/// Content that can be written to or read from the clipboard
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ClipboardContent {
+    /// Plain text
+    Text(String),
+    /// HTML markup with an optional plain text alternative
+    /// for apps that cannot paste HTML
+    Html {
+        /// The HTML markup
+        html: String,
+        /// The plain text alternative
+        alt_text: Option<String>,
+    },
+    /// An image. Only PNG images can be written to the clipboard
+    /// and images read from the clipboard are always PNG images.
+    Image(AssetFile<'static>),
+}
+
+impl ClipboardContent {
+    /// The [ClipboardFormat] of the content
+    pub fn format(&self) -> ClipboardFormat {
+        match self {
+            Self::Text(_) => ClipboardFormat::Text,
+            Self::Html { .. } => ClipboardFormat::Html,
+            Self::Image(_) => ClipboardFormat::Image,
+        }
+    }
+
+    /// The script that dispatches [CLIPBOARD_DOM_EVENT] on `document` with this content
+    pub fn to_dom_event_script(&self) -> String {
+        let content = match self {
+            Self::Text(text) => Cow::Borrowed(text.as_str()),
+            Self::Html { html, .. } => Cow::Borrowed(html.as_str()),
+            Self::Image(image) => image.base64(),
+        };
+
+        String::from("document.dispatchEvent(new CustomEvent(")
+            + &js_string(CLIPBOARD_DOM_EVENT)
+            + ",{detail:{format:"
+            + &js_string(self.format().as_str())
+            + ",content:"
+            + &js_string(&content)
+            + "}}));"
+    }
+}
+
+/// The storage used by [Clipboard]. Implement this trait to use a custom clipboard
+/// like an in-memory clipboard in tests.
+pub trait ClipboardBackend: Send {
+    /// Read the content of the clipboard in the specified format
+    fn read(&mut self, format: ClipboardFormat) -> PuppeteerResult<ClipboardContent>;
+
+    /// Replace the content of the clipboard
+    fn write(&mut self, content: ClipboardContent) -> PuppeteerResult<()>;
+
+    /// Clear the content of the clipboard
+    fn clear(&mut self) -> PuppeteerResult<()>;
+}
+
+/// A clipboard that lives in memory and is not shared with other apps.
+/// This is useful for tests or when the system clipboard is unavailable.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct MemoryClipboard {
+    content: Option<ClipboardContent>,
+}
+
+impl MemoryClipboard {
+    /// Initialize an empty in-memory clipboard
+    pub fn new() -> Self {
+        MemoryClipboard::default()
+    }
+}
+
+impl ClipboardBackend for MemoryClipboard {
+    fn read(&mut self, format: ClipboardFormat) -> PuppeteerResult<ClipboardContent> {
+        let content = match (format, self.content.as_ref()) {
+            (ClipboardFormat::Text, Some(ClipboardContent::Text(text))) => {
+                ClipboardContent::Text(text.clone())
+            }
+            (
+                ClipboardFormat::Text,
+                Some(ClipboardContent::Html {
+                    alt_text: Some(alt_text),
+                    ..
+                }),
+            ) => ClipboardContent::Text(alt_text.clone()),
+            (ClipboardFormat::Html, Some(html @ ClipboardContent::Html { .. })) => html.clone(),
+            (ClipboardFormat::Image, Some(image @ ClipboardContent::Image(_))) => image.clone(),
+            _ => return Err(PuppeteerError::ClipboardContentNotAvailable),
+        };
+
+        Ok(content)
+    }
+
+    fn write(&mut self, content: ClipboardContent) -> PuppeteerResult<()> {
+        if let ClipboardContent::Image(image) = &content {
+            // Ensures the in-memory clipboard behaves like the system clipboard
+            decode_png(image)?;
+        }
+
+        self.content.replace(content);
+
+        Ok(())
+    }
+
+    fn clear(&mut self) -> PuppeteerResult<()> {
+        self.content.take();
+
+        Ok(())
+    }
+}
+
+/// The clipboard of the operating system
+pub struct SystemClipboard(arboard::Clipboard);
+
+impl SystemClipboard {
+    /// Connect to the clipboard of the operating system
+    pub fn new() -> PuppeteerResult<Self> {
+        Ok(SystemClipboard(arboard::Clipboard::new()?))
+    }
+}
+
+impl core::fmt::Debug for SystemClipboard {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SystemClipboard").finish()
+    }
+}
+
+impl ClipboardBackend for SystemClipboard {
+    fn read(&mut self, format: ClipboardFormat) -> PuppeteerResult<ClipboardContent> {
+        let content = match format {
+            ClipboardFormat::Text => ClipboardContent::Text(self.0.get_text()?),
+            ClipboardFormat::Html => ClipboardContent::Html {
+                html: self.0.get().html()?,
+                alt_text: Option::None,
+            },
+            ClipboardFormat::Image => {
+                let image = self.0.get_image()?;
+
+                ClipboardContent::Image(encode_png(
+                    image.width as u32,
+                    image.height as u32,
+                    &image.bytes,
+                )?)
+            }
+        };
+
+        Ok(content)
+    }
+
+    fn write(&mut self, content: ClipboardContent) -> PuppeteerResult<()> {
+        match content {
+            ClipboardContent::Text(text) => self.0.set_text(text)?,
+            ClipboardContent::Html { html, alt_text } => self.0.set_html(html, alt_text)?,
+            ClipboardContent::Image(image) => {
+                let (width, height, rgba) = decode_png(&image)?;
+
+                self.0.set_image(arboard::ImageData {
+                    width: width as usize,
+                    height: height as usize,
+                    bytes: Cow::Owned(rgba),
+                })?
+            }
+        }
+
+        Ok(())
+    }
+
+    fn clear(&mut self) -> PuppeteerResult<()> {
+        Ok(self.0.clear()?)
+    }
+}
+
+/// The clipboard available to the app through [crate::ActiveAppEnv].
+/// It is cheap to clone and all clones share the same [ClipboardBackend].
+///
+/// Any script running in the page can send a [ClipboardRequest], so reading the clipboard
+/// using IPC is denied unless it is enabled using [Clipboard::allow_ipc_reads].
+/// Writing using IPC is always allowed.
+#[derive(Clone)]
+pub struct Clipboard {
+    backend: Arc<Mutex<Box<dyn ClipboardBackend>>>,
+    allow_ipc_reads: bool,
+}
+
+impl Clipboard {
+    /// Use a custom [ClipboardBackend]
+    pub fn new(backend: impl ClipboardBackend + 'static) -> Self {
+        Clipboard {
+            backend: Arc::new(Mutex::new(Box::new(backend))),
+            allow_ipc_reads: false,
+        }
+    }
+
+    /// Allow the page to read the clipboard using a [ClipboardRequest]. Default is `false`
+    pub fn allow_ipc_reads(mut self, allow: bool) -> Self {
+        self.allow_ipc_reads = allow;
+
+        self
+    }
+
+    /// Whether the page can read the clipboard using a [ClipboardRequest]
+    pub fn ipc_reads_allowed(&self) -> bool {
+        self.allow_ipc_reads
+    }
+
+    /// Returns [PuppeteerError::ClipboardIpcReadDenied] if the request sent using IPC
+    /// reads the clipboard and [Clipboard::allow_ipc_reads] is not enabled
+    pub fn authorize_ipc(&self, request: ClipboardRequest) -> PuppeteerResult<ClipboardRequest> {
+        match request {
+            ClipboardRequest::Read(format) if !self.allow_ipc_reads => {
+                Err(PuppeteerError::ClipboardIpcReadDenied(format.as_str()))
+            }
+            _ => Ok(request),
+        }
+    }
+
+    /// Use the clipboard of the operating system
+    pub fn system() -> PuppeteerResult<Self> {
+        Ok(Clipboard::new(SystemClipboard::new()?))
+    }
+
+    /// Use a clipboard that lives in memory
+    pub fn memory() -> Self {
+        Clipboard::new(MemoryClipboard::new())
+    }
+
+    /// Read the content of the clipboard in the specified format
+    pub fn read(&self, format: ClipboardFormat) -> PuppeteerResult<ClipboardContent> {
+        self.backend
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .read(format)
+    }
+
+    /// Replace the content of the clipboard
+    pub fn write(&self, content: ClipboardContent) -> PuppeteerResult<()> {
+        self.backend
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .write(content)
+    }
+
+    /// Clear the content of the clipboard
+    pub fn clear(&self) -> PuppeteerResult<()> {
+        self.backend
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear()
+    }
+
+    /// Read plain text from the clipboard
+    pub fn read_text(&self) -> PuppeteerResult<String> {
+        match self.read(ClipboardFormat::Text)? {
+            ClipboardContent::Text(text) => Ok(text),
+            _ => Err(PuppeteerError::ClipboardContentNotAvailable),
+        }
+    }
+
+    /// Write plain text to the clipboard
+    pub fn write_text(&self, text: &str) -> PuppeteerResult<()> {
+        self.write(ClipboardContent::Text(text.to_owned()))
+    }
+
+    /// Read HTML markup from the clipboard
+    pub fn read_html(&self) -> PuppeteerResult<String> {
+        match self.read(ClipboardFormat::Html)? {
+            ClipboardContent::Html { html, .. } => Ok(html),
+            _ => Err(PuppeteerError::ClipboardContentNotAvailable),
+        }
+    }
+
+    /// Write HTML markup to the clipboard with an optional plain text alternative
+    pub fn write_html(&self, html: &str, alt_text: Option<&str>) -> PuppeteerResult<()> {
+        self.write(ClipboardContent::Html {
+            html: html.to_owned(),
+            alt_text: alt_text.map(|value| value.to_owned()),
+        })
+    }
+
+    /// Read an image from the clipboard as a PNG [AssetFile]
+    pub fn read_image(&self) -> PuppeteerResult<AssetFile<'static>> {
+        match self.read(ClipboardFormat::Image)? {
+            ClipboardContent::Image(image) => Ok(image),
+            _ => Err(PuppeteerError::ClipboardContentNotAvailable),
+        }
+    }
+
+    /// Write a PNG [AssetFile] to the clipboard
+    pub fn write_image(&self, image: AssetFile<'static>) -> PuppeteerResult<()> {
+        self.write(ClipboardContent::Image(image))
+    }
+}
+
+impl core::fmt::Debug for Clipboard {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Clipboard")
+            .field("allow_ipc_reads", &self.allow_ipc_reads)
+            .finish()
+    }
+}
+
+/// A clipboard operation requested by JavaScript using an IPC message.
+///
+/// The IPC messages are:
+/// - `puppeteer:clipboard:write_text>{text}`
+/// - `puppeteer:clipboard:write_html>{html}`
+/// - `puppeteer:clipboard:write_image>{PNG image as a base64 data URI}`
+/// - `puppeteer:clipboard:read_text`
+/// - `puppeteer:clipboard:read_html`
+/// - `puppeteer:clipboard:read_image`
+///
+/// Reading the clipboard dispatches a [CLIPBOARD_DOM_EVENT] on `document`.
+/// The read requests are denied unless [Clipboard::allow_ipc_reads] is enabled.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ClipboardRequest {
+    /// Write content to the clipboard
+    Write(ClipboardContent),
+    /// Read content from the clipboard in the specified format
+    Read(ClipboardFormat),
+}
+
+impl ClipboardRequest {
+    /// Parse an IPC message. Returns `None` if the message is not reserved for the clipboard.
+    /// Messages reserved for the clipboard that cannot be parsed return an error.
+    pub fn from_ipc(message: &str) -> Option<PuppeteerResult<Self>> {
+        let command = message.strip_prefix(CLIPBOARD_IPC_PREFIX)?;
+        let (operation, payload) = match command.split_once('>') {
+            Some((operation, payload)) => (operation, Some(payload)),
+            None => (command, Option::None),
+        };
+
+        let request = match (operation, payload) {
+            ("read_text", None) => Ok(Self::Read(ClipboardFormat::Text)),
+            ("read_html", None) => Ok(Self::Read(ClipboardFormat::Html)),
+            ("read_image", None) => Ok(Self::Read(ClipboardFormat::Image)),
+            ("write_text", Some(text)) => Ok(Self::Write(ClipboardContent::Text(text.to_owned()))),
+            ("write_html", Some(html)) => Ok(Self::Write(ClipboardContent::Html {
+                html: html.to_owned(),
+                alt_text: Option::None,
+            })),
+            ("write_image", Some(data_uri)) => {
+                data_uri_to_asset(data_uri).map(|image| Self::Write(ClipboardContent::Image(image)))
+            }
+            _ => Err(PuppeteerError::InvalidClipboardRequest(message.to_owned())),
+        };
+
+        Some(request)
+    }
+
+    /// Perform the operation. Reading returns the content read.
+    pub fn perform(self, clipboard: &Clipboard) -> PuppeteerResult<Option<ClipboardContent>> {
+        match self {
+            Self::Write(content) => clipboard.write(content).map(|_| Option::None),
+            Self::Read(format) => clipboard.read(format).map(Some),
+        }
+    }
+}
+
+fn data_uri_to_asset(data_uri: &str) -> PuppeteerResult<AssetFile<'static>> {
+    let encoded = data_uri
+        .split_once(";base64,")
+        .map(|(_, encoded)| encoded)
+        .unwrap_or(data_uri);
+
+    let bytes = Base64::decode_vec(encoded)
+        .map_err(|error| PuppeteerError::ClipboardImage(error.to_string()))?;
+
+    Ok(AssetFile {
+        name: Cow::Borrowed(CLIPBOARD_IMAGE_NAME),
+        bytes: BytesMut::from(bytes.as_slice()),
+    })
+}
+
+/// Encode RGBA pixels into a PNG [AssetFile]
+fn encode_png(width: u32, height: u32, rgba: &[u8]) -> PuppeteerResult<AssetFile<'static>> {
+    let mut bytes = Vec::new();
+
+    {
+        let mut encoder = png::Encoder::new(&mut bytes, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(rgba))
+            .map_err(|error| PuppeteerError::ClipboardImage(error.to_string()))?;
+    }
+
+    Ok(AssetFile {
+        name: Cow::Borrowed(CLIPBOARD_IMAGE_NAME),
+        bytes: BytesMut::from(bytes.as_slice()),
+    })
+}
+
+/// Decode a PNG [AssetFile] into its width, height and RGBA pixels
+fn decode_png(image: &AssetFile) -> PuppeteerResult<(u32, u32, Vec<u8>)> {
+    if image.format() != FileFormat::PortableNetworkGraphics {
+        return Err(PuppeteerError::ClipboardImage(format!(
+            "Expected a PNG image but found `{:?}`",
+            image.format()
+        )));
+    }
+
+    let mut decoder = png::Decoder::new(&image.bytes[..]);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+    let mut reader = decoder
+        .read_info()
+        .map_err(|error| PuppeteerError::ClipboardImage(error.to_string()))?;
+    let mut buffer = vec![0u8; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut buffer)
+        .map_err(|error| PuppeteerError::ClipboardImage(error.to_string()))?;
+    buffer.truncate(info.buffer_size());
+
+    let rgba = match info.color_type {
+        png::ColorType::Rgba => buffer,
+        png::ColorType::Rgb => buffer
+            .chunks_exact(3)
+            .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], u8::MAX])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buffer
+            .chunks_exact(2)
+            .flat_map(|pixel| [pixel[0], pixel[0], pixel[0], pixel[1]])
+            .collect(),
+        png::ColorType::Grayscale => buffer
+            .iter()
+            .flat_map(|pixel| [*pixel, *pixel, *pixel, u8::MAX])
+            .collect(),
+        png::ColorType::Indexed => {
+            return Err(PuppeteerError::ClipboardImage(
+                "Indexed PNG images could not be expanded".to_owned(),
+            ))
+        }
+    };
+
+    Ok((info.width, info.height, rgba))
+}
+
+#[cfg(test)]
+mod clipboard_checks {
+    use super::*;
+
+    #[test]
+    fn memory_clipboard() {
+        let clipboard = Clipboard::memory();
+
+        assert!(matches!(
+            clipboard.read_text(),
+            Err(PuppeteerError::ClipboardContentNotAvailable)
+        ));
+
+        clipboard.write_text("Hello Puppeteer").unwrap();
+        assert_eq!("Hello Puppeteer", clipboard.read_text().unwrap());
+        assert!(clipboard.read_html().is_err());
+
+        clipboard.write_html("<b>Hello</b>", Some("Hello")).unwrap();
+        assert_eq!("<b>Hello</b>", clipboard.read_html().unwrap());
+        assert_eq!("Hello", clipboard.read_text().unwrap());
+
+        // Clones share the same backend
+        let image = encode_png(2, 1, &[255, 0, 0, 255, 0, 0, 255, 128]).unwrap();
+        clipboard.clone().write_image(image.clone()).unwrap();
+        assert_eq!(image, clipboard.read_image().unwrap());
+        assert_eq!(
+            (2, 1, vec![255, 0, 0, 255, 0, 0, 255, 128]),
+            decode_png(&clipboard.read_image().unwrap()).unwrap()
+        );
+
+        clipboard.clear().unwrap();
+        assert!(clipboard.read_image().is_err());
+
+        let not_png = AssetFile {
+            name: Cow::Borrowed("text.txt"),
+            bytes: BytesMut::from(&b"plain text"[..]),
+        };
+        assert!(clipboard.write_image(not_png).is_err());
+    }
+
+    #[test]
+    fn ipc_requests() {
+        assert!(ClipboardRequest::from_ipc("inc").is_none());
+        assert_eq!(
+            ClipboardRequest::Read(ClipboardFormat::Image),
+            ClipboardRequest::from_ipc("puppeteer:clipboard:read_image")
+                .unwrap()
+                .unwrap()
+        );
+        assert_eq!(
+            ClipboardRequest::Write(ClipboardContent::Text("a>b".to_owned())),
+            ClipboardRequest::from_ipc("puppeteer:clipboard:write_text>a>b")
+                .unwrap()
+                .unwrap()
+        );
+        assert!(ClipboardRequest::from_ipc("puppeteer:clipboard:read_text>")
+            .unwrap()
+            .is_err());
+
+        let image = encode_png(1, 1, &[1, 2, 3, 4]).unwrap();
+        let message = String::from("puppeteer:clipboard:write_image>") + &image.base64();
+        let clipboard = Clipboard::memory();
+        let write = ClipboardRequest::from_ipc(&message).unwrap().unwrap();
+        clipboard
+            .authorize_ipc(write)
+            .unwrap()
+            .perform(&clipboard)
+            .unwrap();
+
+        assert!(matches!(
+            clipboard.authorize_ipc(ClipboardRequest::Read(ClipboardFormat::Text)),
+            Err(PuppeteerError::ClipboardIpcReadDenied("text"))
+        ));
+        assert!(clipboard
+            .clone()
+            .allow_ipc_reads(true)
+            .authorize_ipc(ClipboardRequest::Read(ClipboardFormat::Text))
+            .is_ok());
+
+        assert_eq!(
+            Some(ClipboardContent::Image(image)),
+            ClipboardRequest::Read(ClipboardFormat::Image)
+                .perform(&clipboard)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn dom_event_script() {
+        let script =
+            ClipboardContent::Text("`${alert(1)}`\"</script>".to_owned()).to_dom_event_script();
+
+        assert_eq!(
+            r#"document.dispatchEvent(new CustomEvent("puppeteer:clipboard",{detail:{format:"text",content:"`${alert(1)}`\"\u003c/script\u003e"}}));"#,
+            script
+        );
+    }
+}
@@ -0,0 +1,462 @@
+use crate::{ColorPalette, PuppeteerError, PuppeteerResult};
+use std::str::FromStr;
+
+/// The minimum WCAG 2 contrast ratio of normal text for the AA level
+pub const WCAG_AA_CONTRAST: f64 = 4.5;
+
+/// The minimum WCAG 2 contrast ratio of large text and icons for the AA level
+pub const WCAG_AA_LARGE_CONTRAST: f64 = 3.0;
+
+/// A validated sRGB color with an alpha channel.
+/// It is parsed from the `#RGB`, `#RGBA`, `#RRGGBB` and `#RRGGBBAA` CSS notations
+///
+/// #### Example
+/// ```rust
+/// use puppeteer::HexColor;
+///
+/// let color: HexColor = "#0af".parse().unwrap();
+/// assert_eq!(HexColor::rgb(0x00, 0xAA, 0xFF), color);
+/// assert_eq!("#00AAFF80", color.with_alpha(0x80).to_string());
+/// ```
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct HexColor {
+    red: u8,
+    green: u8,
+    blue: u8,
+    alpha: u8,
+}
+
+impl HexColor {
+    /// An opaque color
+    pub const fn rgb(red: u8, green: u8, blue: u8) -> Self {
+        HexColor::rgba(red, green, blue, u8::MAX)
+    }
+
+    /// A color with an alpha channel where `0` is transparent and `255` is opaque
+    pub const fn rgba(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        HexColor {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+
+    /// Parse the hex notation of a color. The `#` is required
+    pub fn parse(value: &str) -> PuppeteerResult<Self> {
+        let invalid = || PuppeteerError::InvalidHexColor(value.to_owned());
+
+        let digits = value.strip_prefix('#').ok_or_else(invalid)?;
+
+        if !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        let channel = |index: usize, width: usize| {
+            let digits = &digits[index * width..(index + 1) * width];
+            // A single digit is repeated like `#f00` for `#ff0000`
+            let digits = if width == 1 {
+                digits.repeat(2)
+            } else {
+                digits.to_owned()
+            };
+
+            u8::from_str_radix(&digits, 16).map_err(|_| invalid())
+        };
+
+        match digits.len() {
+            3 => Ok(HexColor::rgb(
+                channel(0, 1)?,
+                channel(1, 1)?,
+                channel(2, 1)?,
+            )),
+            4 => Ok(HexColor::rgba(
+                channel(0, 1)?,
+                channel(1, 1)?,
+                channel(2, 1)?,
+                channel(3, 1)?,
+            )),
+            6 => Ok(HexColor::rgb(
+                channel(0, 2)?,
+                channel(1, 2)?,
+                channel(2, 2)?,
+            )),
+            8 => Ok(HexColor::rgba(
+                channel(0, 2)?,
+                channel(1, 2)?,
+                channel(2, 2)?,
+                channel(3, 2)?,
+            )),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// The red channel
+    pub fn red(&self) -> u8 {
+        self.red
+    }
+
+    /// The green channel
+    pub fn green(&self) -> u8 {
+        self.green
+    }
+
+    /// The blue channel
+    pub fn blue(&self) -> u8 {
+        self.blue
+    }
+
+    /// The alpha channel
+    pub fn alpha(&self) -> u8 {
+        self.alpha
+    }
+
+    /// Is the color fully opaque
+    pub fn is_opaque(&self) -> bool {
+        self.alpha == u8::MAX
+    }
+
+    /// The same color with another alpha channel
+    pub fn with_alpha(mut self, alpha: u8) -> Self {
+        self.alpha = alpha;
+
+        self
+    }
+
+    /// The channels as `[red, green, blue, alpha]`
+    pub fn to_array(&self) -> [u8; 4] {
+        [self.red, self.green, self.blue, self.alpha]
+    }
+
+    /// The hex notation, `#RRGGBB` for opaque colors and `#RRGGBBAA` otherwise
+    pub fn to_hex(&self) -> String {
+        let hex = format!("#{:02X}{:02X}{:02X}", self.red, self.green, self.blue);
+
+        if self.is_opaque() {
+            hex
+        } else {
+            hex + &format!("{:02X}", self.alpha)
+        }
+    }
+
+    /// The relative luminance as defined by WCAG 2, from `0.0` for black to `1.0` for white.
+    /// The alpha channel is ignored
+    pub fn relative_luminance(&self) -> f64 {
+        let linear = |channel: u8| {
+            let channel = f64::from(channel) / 255.0;
+
+            if channel <= 0.04045 {
+                channel / 12.92
+            } else {
+                ((channel + 0.055) / 1.055).powf(2.4)
+            }
+        };
+
+        0.2126 * linear(self.red) + 0.7152 * linear(self.green) + 0.0722 * linear(self.blue)
+    }
+
+    /// The WCAG 2 contrast ratio with the other color, from `1.0` to `21.0`
+    pub fn contrast_ratio(&self, other: &HexColor) -> f64 {
+        let (first, second) = (self.relative_luminance(), other.relative_luminance());
+
+        (first.max(second) + 0.05) / (first.min(second) + 0.05)
+    }
+
+    /// Black or white, whichever has the most contrast on this color
+    pub fn readable_text(&self) -> HexColor {
+        let black = HexColor::rgb(0, 0, 0);
+        let white = HexColor::rgb(0xFF, 0xFF, 0xFF);
+
+        if self.contrast_ratio(&black) >= self.contrast_ratio(&white) {
+            black
+        } else {
+            white
+        }
+    }
+
+    /// The hue in degrees, the saturation and the lightness from `0.0` to `1.0`
+    pub fn to_hsl(&self) -> (f64, f64, f64) {
+        let [red, green, blue] = [self.red, self.green, self.blue].map(|c| f64::from(c) / 255.0);
+        let max = red.max(green).max(blue);
+        let min = red.min(green).min(blue);
+        let lightness = (max + min) / 2.0;
+        let delta = max - min;
+
+        if delta == 0.0 {
+            return (0.0, 0.0, lightness);
+        }
+
+        let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+        let hue = if max == red {
+            60.0 * ((green - blue) / delta).rem_euclid(6.0)
+        } else if max == green {
+            60.0 * ((blue - red) / delta + 2.0)
+        } else {
+            60.0 * ((red - green) / delta + 4.0)
+        };
+
+        (hue, saturation, lightness)
+    }
+
+    /// An opaque color from the hue in degrees, the saturation and the lightness from `0.0` to `1.0`
+    pub fn from_hsl(hue: f64, saturation: f64, lightness: f64) -> Self {
+        let hue = hue.rem_euclid(360.0);
+        let saturation = saturation.clamp(0.0, 1.0);
+        let lightness = lightness.clamp(0.0, 1.0);
+
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let x = chroma * (1.0 - ((hue / 60.0).rem_euclid(2.0) - 1.0).abs());
+        let m = lightness - chroma / 2.0;
+
+        let (red, green, blue) = match hue {
+            hue if hue < 60.0 => (chroma, x, 0.0),
+            hue if hue < 120.0 => (x, chroma, 0.0),
+            hue if hue < 180.0 => (0.0, chroma, x),
+            hue if hue < 240.0 => (0.0, x, chroma),
+            hue if hue < 300.0 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+
+        let channel = |value: f64| ((value + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+
+        HexColor::rgb(channel(red), channel(green), channel(blue))
+    }
+
+    /// The same hue with another saturation and lightness, from `0.0` to `1.0`
+    pub fn tone(&self, saturation: f64, lightness: f64) -> Self {
+        let (hue, _, _) = self.to_hsl();
+
+        HexColor::from_hsl(hue, saturation, lightness).with_alpha(self.alpha)
+    }
+
+    /// The CSS `rgba()` notation
+    pub fn to_rgba(&self) -> String {
+        format!(
+            "rgba({}, {}, {}, {})",
+            self.red,
+            self.green,
+            self.blue,
+            // Rounded to 3 decimals like the browsers do
+            (f32::from(self.alpha) / 255.0 * 1000.0).round() / 1000.0
+        )
+    }
+}
+
+impl Default for HexColor {
+    fn default() -> Self {
+        HexColor::rgb(0, 0, 0)
+    }
+}
+
+impl FromStr for HexColor {
+    type Err = PuppeteerError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        HexColor::parse(value)
+    }
+}
+
+impl TryFrom<&str> for HexColor {
+    type Error = PuppeteerError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        HexColor::parse(value)
+    }
+}
+
+impl From<[u8; 4]> for HexColor {
+    fn from([red, green, blue, alpha]: [u8; 4]) -> Self {
+        HexColor::rgba(red, green, blue, alpha)
+    }
+}
+
+impl From<[u8; 3]> for HexColor {
+    fn from([red, green, blue]: [u8; 3]) -> Self {
+        HexColor::rgb(red, green, blue)
+    }
+}
+
+impl core::fmt::Display for HexColor {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+/// How serious a [ContrastIssue] is
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum ContrastSeverity {
+    /// The ratio is below [WCAG_AA_CONTRAST] so small text is hard to read
+    Warning,
+    /// The ratio is below [WCAG_AA_LARGE_CONTRAST] so even icons and large text are hard to read
+    Error,
+}
+
+/// A pair of colors used by Puppeteer that does not have enough contrast
+#[derive(Debug, PartialEq, Clone)]
+pub struct ContrastIssue {
+    /// The element using the colors, like `title bar icon`
+    pub element: &'static str,
+    /// The color of the text or the icon
+    pub foreground: HexColor,
+    /// The color behind the text or the icon
+    pub background: HexColor,
+    /// The WCAG 2 contrast ratio
+    pub ratio: f64,
+    /// How serious the issue is
+    pub severity: ContrastSeverity,
+}
+
+impl core::fmt::Display for ContrastIssue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} uses {} on {} with a contrast ratio of {:.2}:1",
+            self.element, self.foreground, self.background, self.ratio
+        )
+    }
+}
+
+impl ColorPalette {
+    /// The foreground and background colors of the elements styled by Puppeteer
+    /// like the title bar, the context menus, the modals and the toasts
+    pub fn contrast_pairs(&self) -> [(&'static str, HexColor, HexColor); 10] {
+        [
+            ("body text", self.text, self.background),
+            ("title bar icon", self.secondary, self.primary),
+            ("title bar icon on hover", self.text, self.tertiary),
+            ("context menu", self.text, self.surface),
+            ("modal", self.text, self.surface),
+            ("modal button", self.text, self.tertiary),
+            ("info toast", self.text, self.tertiary),
+            ("success toast", self.success.readable_text(), self.success),
+            ("warning toast", self.warning.readable_text(), self.warning),
+            ("error toast", self.error.readable_text(), self.error),
+        ]
+    }
+
+    /// Compute the WCAG 2 contrast ratios of the [ColorPalette::contrast_pairs]
+    /// and return the pairs that are below [WCAG_AA_CONTRAST]
+    pub fn audit_contrast(&self) -> Vec<ContrastIssue> {
+        self.contrast_pairs()
+            .into_iter()
+            .filter_map(|(element, foreground, background)| {
+                let ratio = foreground.contrast_ratio(&background);
+
+                let severity = if ratio < WCAG_AA_LARGE_CONTRAST {
+                    ContrastSeverity::Error
+                } else if ratio < WCAG_AA_CONTRAST {
+                    ContrastSeverity::Warning
+                } else {
+                    return None;
+                };
+
+                Some(ContrastIssue {
+                    element,
+                    foreground,
+                    background,
+                    ratio,
+                    severity,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns an error listing the [ContrastSeverity::Error] issues of [ColorPalette::audit_contrast]
+    pub fn check_contrast(&self) -> PuppeteerResult<()> {
+        let errors = self
+            .audit_contrast()
+            .iter()
+            .filter(|issue| issue.severity == ContrastSeverity::Error)
+            .map(|issue| issue.to_string())
+            .collect::<Vec<String>>();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(PuppeteerError::InsufficientContrast(errors.join(", ")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod color_checks {
+    use super::*;
+
+    #[test]
+    fn parses() {
+        assert_eq!(HexColor::rgb(255, 0, 0), HexColor::parse("#f00").unwrap());
+        assert_eq!(
+            HexColor::rgba(0x11, 0x22, 0x33, 0x44),
+            HexColor::parse("#1234").unwrap()
+        );
+        assert_eq!(
+            HexColor::rgb(0xE6, 0xE6, 0xE6),
+            "#e6E6e6".parse::<HexColor>().unwrap()
+        );
+        assert_eq!(
+            HexColor::rgba(0, 0, 0, 0x80),
+            HexColor::try_from("#00000080").unwrap()
+        );
+
+        for invalid in ["", "FFFFFF", "#FFFFF", "#GGGGGG", "#+FFFFF", "#ÿÿÿ"] {
+            assert!(HexColor::parse(invalid).is_err(), "{invalid}");
+        }
+
+        assert_eq!("#E6E6E6", HexColor::rgb(230, 230, 230).to_string());
+        assert_eq!("#00000080", HexColor::rgba(0, 0, 0, 128).to_hex());
+        assert_eq!(
+            "rgba(0, 0, 0, 0.502)",
+            HexColor::rgba(0, 0, 0, 128).to_rgba()
+        );
+        assert_eq!("rgba(1, 2, 3, 1)", HexColor::from([1, 2, 3]).to_rgba());
+
+        let (hue, saturation, lightness) = HexColor::rgb(0x33, 0x66, 0x99).to_hsl();
+        assert_eq!(
+            HexColor::rgb(0x33, 0x66, 0x99),
+            HexColor::from_hsl(hue, saturation, lightness)
+        );
+        assert_eq!(
+            21.0,
+            HexColor::rgb(0, 0, 0).contrast_ratio(&HexColor::rgb(0xFF, 0xFF, 0xFF))
+        );
+        assert_eq!(
+            HexColor::rgb(0xFF, 0xFF, 0xFF),
+            HexColor::rgb(0xD3, 0x2F, 0x2F).readable_text()
+        );
+
+        assert!(crate::UiPaint::to_html(&crate::Shell::new())
+            .contains("<style>:root {--pp-primary: #FFFFFF;--pp-secondary: #000000;"));
+    }
+
+    #[test]
+    fn audits_contrast() {
+        for palette in [ColorPalette::light(), ColorPalette::dark()] {
+            assert_eq!(Vec::<ContrastIssue>::new(), palette.audit_contrast());
+        }
+
+        for seed in ["#6750A4", "#FFEB3B", "#00BCD4", "#000000", "#FFFFFF"] {
+            let seed = HexColor::parse(seed).unwrap();
+
+            for palette in [
+                ColorPalette::from_seed(seed),
+                ColorPalette::dark_from_seed(seed),
+            ] {
+                assert_eq!(seed, palette.primary);
+                assert_eq!(Vec::<ContrastIssue>::new(), palette.audit_contrast());
+            }
+        }
+
+        let unreadable = ColorPalette {
+            text: HexColor::rgb(0x77, 0x77, 0x77),
+            surface: HexColor::rgb(0x88, 0x88, 0x88),
+            ..ColorPalette::light()
+        };
+        let issues = unreadable.audit_contrast();
+        assert_eq!(6, issues.len());
+        assert_eq!("body text", issues[0].element);
+        assert_eq!(ContrastSeverity::Warning, issues[0].severity);
+        assert_eq!("context menu", issues[2].element);
+        assert_eq!(ContrastSeverity::Error, issues[2].severity);
+        assert!(unreadable.check_contrast().is_err());
+    }
+}
@@ -0,0 +1,313 @@
+use crate::{ActiveAppEnv, ModifyView, PuppeteerError, PuppeteerResult, VNode};
+use async_trait::async_trait;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// The prefix of the IPC messages sent to a [Component].
+/// The full message is `puppeteer:component>{mount id}>{payload}`
+pub const COMPONENT_IPC_PREFIX: &str = "puppeteer:component>";
+
+/// A reusable part of the view with its own state that renders into the node
+/// with its mount ID and handles its own messages.
+///
+/// Messages created using [ComponentScope::message] are routed to the instance
+/// that rendered them and, after [Component::update], only the node of that
+/// instance is patched using the [crate::VDom] of the [ActiveAppEnv].
+///
+/// #### Example
+/// ```rust
+/// use puppeteer::{async_trait, ActiveAppEnv, Component, ComponentScope, Html, VNode};
+///
+/// struct Counter {
+///     id: String,
+///     count: u64,
+/// }
+///
+/// enum CounterMessage {
+///     Increment,
+/// }
+///
+/// #[async_trait::async_trait]
+/// impl Component for Counter {
+///     type Message = CounterMessage;
+///
+///     fn mount_id(&self) -> String {
+///         self.id.clone()
+///     }
+///
+///     fn parse(payload: &str) -> Option<Self::Message> {
+///         (payload == "increment").then_some(CounterMessage::Increment)
+///     }
+///
+///     async fn update(&mut self, message: Self::Message, _app_env: &ActiveAppEnv) {
+///         match message {
+///             CounterMessage::Increment => self.count += 1,
+///         }
+///     }
+///
+///     fn render(&self, scope: &ComponentScope) -> VNode {
+///         Html::button()
+///             .on("click", &scope.message("increment"))
+///             .child(Html::text(&self.count.to_string()))
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait Component: Send + 'static {
+    /// The messages handled by the component
+    type Message: Send;
+
+    /// The `id` of the node the component renders into. It must be unique
+    /// for each mounted instance and must not contain `>`
+    fn mount_id(&self) -> String;
+
+    /// Parse the payload of a message sent to this instance
+    fn parse(payload: &str) -> Option<Self::Message>;
+
+    /// Update the state of the component. The component is rendered again afterwards
+    async fn update(&mut self, message: Self::Message, app_env: &ActiveAppEnv);
+
+    /// Render the content of the mount node
+    fn render(&self, scope: &ComponentScope) -> VNode;
+}
+
+/// Namespaces the IPC messages and element IDs of a [Component] instance
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct ComponentScope {
+    id: String,
+}
+
+impl ComponentScope {
+    /// The mount ID of the instance
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    /// The IPC message routed to this instance with the `payload`
+    pub fn message(&self, payload: &str) -> String {
+        String::from(COMPONENT_IPC_PREFIX) + &self.id + ">" + payload
+    }
+
+    /// An element ID that does not collide with the IDs of other instances
+    pub fn element_id(&self, local_id: &str) -> String {
+        self.id.clone() + "-" + local_id
+    }
+}
+
+/// An IPC message sent to a [Component]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct ComponentMessage {
+    /// The mount ID of the instance
+    pub id: String,
+    /// The payload passed to [Component::parse]
+    pub payload: String,
+}
+
+impl ComponentMessage {
+    /// Returns `None` if the IPC message is not sent to a component
+    pub fn from_ipc(message: &str) -> Option<Self> {
+        let (id, payload) = message
+            .strip_prefix(COMPONENT_IPC_PREFIX)?
+            .split_once('>')?;
+
+        Some(ComponentMessage {
+            id: id.to_owned(),
+            payload: payload.to_owned(),
+        })
+    }
+}
+
+#[async_trait]
+trait MountedComponent: Send {
+    async fn handle(&mut self, payload: &str, app_env: &ActiveAppEnv) -> PuppeteerResult<()>;
+
+    fn render(&self, scope: &ComponentScope) -> VNode;
+}
+
+#[async_trait]
+impl<C: Component> MountedComponent for C {
+    async fn handle(&mut self, payload: &str, app_env: &ActiveAppEnv) -> PuppeteerResult<()> {
+        let message = C::parse(payload)
+            .ok_or_else(|| PuppeteerError::InvalidComponentMessage(payload.to_owned()))?;
+
+        self.update(message, app_env).await;
+
+        Ok(())
+    }
+
+    fn render(&self, scope: &ComponentScope) -> VNode {
+        Component::render(self, scope)
+    }
+}
+
+type SharedComponent = Arc<smol::lock::Mutex<Box<dyn MountedComponent>>>;
+
+/// The [Component] instances that are mounted, shared by the event handlers
+#[derive(Default, Clone)]
+pub struct Components {
+    mounted: Arc<Mutex<HashMap<String, SharedComponent>>>,
+}
+
+impl Components {
+    /// Create a registry without any mounted component
+    pub fn new() -> Self {
+        Components::default()
+    }
+
+    /// Mount the instance and render it into the node with its mount ID,
+    /// replacing any instance mounted with the same ID.
+    /// The node must exist, for example by rendering it in the view
+    /// in the same [ModifyView::Batch]
+    pub fn mount(
+        &self,
+        component: impl Component,
+        app_env: &ActiveAppEnv,
+    ) -> PuppeteerResult<ModifyView> {
+        let id = component.mount_id();
+
+        if id.is_empty() || id.contains('>') {
+            return Err(PuppeteerError::InvalidComponentId(id));
+        }
+
+        let scope = ComponentScope { id: id.clone() };
+        let tree = Component::render(&component, &scope);
+
+        self.lock().insert(
+            id.clone(),
+            Arc::new(smol::lock::Mutex::new(Box::new(component))),
+        );
+        app_env.vdom.forget(&id);
+
+        Ok(app_env.vdom.render(&id, tree))
+    }
+
+    /// Remove the instance. The content of its node is left as is
+    pub fn unmount(&self, id: &str, app_env: &ActiveAppEnv) {
+        self.lock().remove(id);
+        app_env.vdom.forget(id);
+    }
+
+    /// Is an instance mounted with the `id`
+    pub fn is_mounted(&self, id: &str) -> bool {
+        self.lock().contains_key(id)
+    }
+
+    /// Route the message to its instance and patch the node of the instance
+    pub async fn dispatch(
+        &self,
+        message: ComponentMessage,
+        app_env: &ActiveAppEnv,
+    ) -> PuppeteerResult<ModifyView> {
+        let component = self
+            .lock()
+            .get(&message.id)
+            .cloned()
+            .ok_or_else(|| PuppeteerError::ComponentNotMounted(message.id.clone()))?;
+
+        let mut component = component.lock().await;
+        component.handle(&message.payload, app_env).await?;
+
+        let tree = component.render(&ComponentScope {
+            id: message.id.clone(),
+        });
+
+        Ok(app_env.vdom.render(&message.id, tree))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, SharedComponent>> {
+        self.mounted
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl core::fmt::Debug for Components {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Components")
+            .field("mounted", &self.lock().keys().collect::<Vec<&String>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod component_checks {
+    use super::*;
+    use crate::{DomPatch, Html};
+
+    struct Counter {
+        id: &'static str,
+        count: u64,
+    }
+
+    #[async_trait]
+    impl Component for Counter {
+        type Message = u64;
+
+        fn mount_id(&self) -> String {
+            self.id.to_owned()
+        }
+
+        fn parse(payload: &str) -> Option<Self::Message> {
+            payload.strip_prefix("add:")?.parse().ok()
+        }
+
+        async fn update(&mut self, message: Self::Message, _app_env: &ActiveAppEnv) {
+            self.count += message;
+        }
+
+        fn render(&self, scope: &ComponentScope) -> VNode {
+            Html::button()
+                .id(&scope.element_id("button"))
+                .on("click", &scope.message("add:1"))
+                .child(Html::text(&self.count.to_string()))
+        }
+    }
+
+    #[test]
+    fn routes_messages() {
+        let app_env = ActiveAppEnv::new("component_checks");
+        let components = Components::new();
+
+        for id in ["first", "second"] {
+            assert!(matches!(
+                components.mount(Counter { id, count: 0 }, &app_env),
+                Ok(ModifyView::ReplaceNodeWithId { .. })
+            ));
+        }
+        assert!(components
+            .mount(
+                Counter {
+                    id: "a>b",
+                    count: 0
+                },
+                &app_env
+            )
+            .is_err());
+
+        let message =
+            ComponentMessage::from_ipc("puppeteer:component>second>add:2").expect("Valid message");
+
+        assert_eq!(
+            ModifyView::Patch {
+                id: "second".to_owned(),
+                patches: vec![DomPatch::SetText {
+                    path: vec![0],
+                    text: "2".to_owned(),
+                }],
+            },
+            smol::block_on(components.dispatch(message, &app_env)).expect("Mounted component")
+        );
+
+        assert!(smol::block_on(components.dispatch(
+            ComponentMessage::from_ipc("puppeteer:component>first>remove").expect("Valid message"),
+            &app_env
+        ))
+        .is_err());
+
+        components.unmount("first", &app_env);
+        assert!(!components.is_mounted("first"));
+        assert!(ComponentMessage::from_ipc("puppeteer:component>first").is_none());
+    }
+}
@@ -0,0 +1,381 @@
+use crate::{escape_html, PuppeteerError, UiPaint};
+use base64ct::{Base64, Encoding};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+};
+
+/// The prefix of the IPC messages sent when the webview blocks a resource.
+/// The full message is `puppeteer:csp>{nonce}>{directive}>{blocked URI}`
+pub const CSP_VIOLATION_IPC_PREFIX: &str = "puppeteer:csp>";
+
+/// The number of random bytes of a script nonce
+pub const CSP_NONCE_BYTES: usize = 16;
+
+/// A Content-Security-Policy added to the `<head>` of the [crate::Shell] as a `<meta>` tag.
+///
+/// The default policy is strict: nothing can be loaded except the scripts of the [crate::Shell],
+/// which are given a random nonce, inline styles, `data:` fonts and images and the sources
+/// of the `head_links`. Inline event handlers are blocked, use [crate::VNode::on] instead.
+/// Violations are sent to `Puppeteer::error_handler()` as
+/// [PuppeteerError::ContentSecurityPolicyViolation].
+///
+/// #### Example
+/// ```rust
+/// use puppeteer::{ContentSecurityPolicy, Shell};
+///
+/// let shell = Shell::new().with_content_security_policy(
+///     ContentSecurityPolicy::new().allow("img-src", "https://images.example.com"),
+/// );
+/// ```
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct ContentSecurityPolicy {
+    nonce: String,
+    directives: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ContentSecurityPolicy {
+    /// Create the strict default policy with a new random nonce
+    pub fn new() -> Self {
+        ContentSecurityPolicy::default()
+    }
+
+    /// Allow the `source` like `https://example.com` or `'self'` for the `directive` like `img-src`
+    pub fn allow(mut self, directive: &str, source: &str) -> Self {
+        self.directives
+            .entry(directive.to_owned())
+            .or_default()
+            .insert(source.to_owned());
+
+        self
+    }
+
+    /// The nonce added to the `<script>` elements of the [crate::Shell]
+    pub fn nonce(&self) -> &str {
+        self.nonce.as_str()
+    }
+
+    /// The sources allowed for the `directive`
+    pub fn sources(&self, directive: &str) -> Vec<&str> {
+        self.directives
+            .get(directive)
+            .map(|sources| sources.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// The policy in the format of the `Content-Security-Policy` header
+    pub fn policy(&self) -> String {
+        self.directives
+            .iter()
+            .map(|(directive, sources)| {
+                sources
+                    .iter()
+                    .fold(directive.clone(), |policy, source| policy + " " + source)
+            })
+            .collect::<Vec<String>>()
+            .join("; ")
+    }
+
+    /// Allow the source of a `<link>` or `<script>` element added to the `<head>`.
+    /// Stylesheets are allowed in `style-src`, preloaded resources in the directive
+    /// of their `as` attribute, icons in `img-src` and scripts in `script-src`
+    pub(crate) fn allow_head_link(self, element: &str) -> Self {
+        let tag = element
+            .trim_start()
+            .trim_start_matches('<')
+            .split(|character: char| character.is_whitespace() || character == '>')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        let (directive, url) = match tag.as_str() {
+            "script" => (
+                "script-src",
+                ContentSecurityPolicy::attribute(element, "src"),
+            ),
+            "link" => {
+                let rel = ContentSecurityPolicy::attribute(element, "rel")
+                    .unwrap_or_default()
+                    .to_ascii_lowercase();
+                let directive = match ContentSecurityPolicy::attribute(element, "as") {
+                    Some("font") => "font-src",
+                    Some("script") => "script-src",
+                    Some("image") => "img-src",
+                    _ if rel.contains("icon") => "img-src",
+                    _ if rel.contains("stylesheet") || rel.contains("preload") => "style-src",
+                    _ => return self,
+                };
+
+                (directive, ContentSecurityPolicy::attribute(element, "href"))
+            }
+            _ => return self,
+        };
+
+        match url.and_then(ContentSecurityPolicy::source) {
+            Some(source) => self.allow(directive, &source),
+            None => self,
+        }
+    }
+
+    /// Add the nonce to the opening tag of each `<script>` element of an entry.
+    /// The content of a script runs until its closing tag, so a `<script` inside
+    /// a string literal of the script is kept as it is
+    pub(crate) fn add_nonce(&self, entry: &str) -> String {
+        let mut outcome = String::with_capacity(entry.len());
+        let mut rest = entry;
+
+        while let Some(index) = rest.find("<script") {
+            let (before, after) = rest.split_at(index + "<script".len());
+            outcome.push_str(before);
+            rest = after;
+
+            if !after.starts_with(|character: char| character == '>' || character.is_whitespace()) {
+                continue;
+            }
+
+            outcome.push_str(r#" nonce=""#);
+            outcome.push_str(&self.nonce);
+            outcome.push('"');
+
+            let content_end = rest
+                .find('>')
+                .and_then(|open_end| {
+                    rest[open_end..]
+                        .find("</script")
+                        .map(|index| open_end + index)
+                })
+                .unwrap_or(rest.len());
+            outcome.push_str(&rest[..content_end]);
+            rest = &rest[content_end..];
+        }
+
+        outcome.push_str(rest);
+
+        outcome
+    }
+
+    /// The value of the attribute in a single element
+    fn attribute<'a>(element: &'a str, name: &str) -> Option<&'a str> {
+        let lowercase = element.to_ascii_lowercase();
+        let mut offset = 0;
+
+        while let Some(index) = lowercase[offset..].find(name) {
+            let start = offset + index;
+            offset = start + name.len();
+
+            let preceded_by_space = lowercase[..start].ends_with(char::is_whitespace);
+            let value = element[offset..].trim_start();
+
+            if let (true, Some(value)) = (preceded_by_space, value.strip_prefix('=')) {
+                let value = value.trim_start();
+                let quote = value.chars().next()?;
+
+                return if quote == '"' || quote == '\'' {
+                    value[1..].split(quote).next()
+                } else {
+                    value
+                        .split(|character: char| character.is_whitespace() || character == '>')
+                        .next()
+                };
+            }
+        }
+
+        None
+    }
+
+    /// The CSP source of a URL, the origin of remote URLs or `'self'` for relative URLs
+    fn source(url: &str) -> Option<String> {
+        let url = url.trim();
+
+        if url.starts_with("data:") {
+            return Some(String::from("data:"));
+        }
+
+        match url.split_once("://") {
+            Some((scheme, rest)) => {
+                let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+
+                (!host.is_empty()).then(|| scheme.to_ascii_lowercase() + "://" + host)
+            }
+            None if url.starts_with("//") => None,
+            None => Some(String::from("'self'")),
+        }
+    }
+}
+
+impl Default for ContentSecurityPolicy {
+    fn default() -> Self {
+        let mut nonce = [0u8; CSP_NONCE_BYTES];
+
+        if let Err(error) = getrandom::getrandom(&mut nonce) {
+            tracing::warn!(
+                "COULD NOT GENERATE A RANDOM CSP NONCE, USING THE CLOCK INSTEAD: {error}"
+            );
+
+            let mut hasher = blake3::Hasher::new();
+            hasher.update(
+                format!("{:?}{}", std::time::SystemTime::now(), std::process::id()).as_bytes(),
+            );
+            hasher.finalize_xof().fill(&mut nonce);
+        }
+
+        let nonce = Base64::encode_string(&nonce);
+
+        ContentSecurityPolicy {
+            directives: BTreeMap::default(),
+            nonce: nonce.clone(),
+        }
+        .allow("default-src", "'none'")
+        .allow("script-src", &(String::from("'nonce-") + &nonce + "'"))
+        .allow("style-src", "'unsafe-inline'")
+        .allow("img-src", "'self'")
+        .allow("img-src", "data:")
+        .allow("font-src", "data:")
+        .allow("connect-src", "ipc:")
+        .allow("connect-src", "http://ipc.localhost")
+        .allow("base-uri", "'none'")
+        .allow("form-action", "'none'")
+    }
+}
+
+impl UiPaint for ContentSecurityPolicy {
+    fn to_html(&self) -> Cow<str> {
+        Cow::Owned(
+            String::from(r#"<meta http-equiv="Content-Security-Policy" content=""#)
+                + &escape_html(&self.policy())
+                + r#"">"#,
+        )
+    }
+}
+
+/// A resource blocked by the [ContentSecurityPolicy]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct CspViolation {
+    /// The directive that blocked the resource like `script-src`
+    pub directive: String,
+    /// The URI of the resource or `inline` and `eval` for inline scripts and `eval()`
+    pub blocked_uri: String,
+}
+
+impl CspViolation {
+    /// Returns `None` if the IPC message is not a violation report sent with the `nonce`
+    /// of the [ContentSecurityPolicy], which only the [crate::PUPPETEER_RUNTIME_SCRIPT] knows
+    pub fn from_ipc(message: &str, nonce: &str) -> Option<Self> {
+        let (sender, report) = message
+            .strip_prefix(CSP_VIOLATION_IPC_PREFIX)?
+            .split_once('>')?;
+
+        if nonce.is_empty() || sender != nonce {
+            return None;
+        }
+
+        let (directive, blocked_uri) = report.split_once('>')?;
+
+        Some(CspViolation {
+            directive: directive.to_owned(),
+            blocked_uri: blocked_uri.to_owned(),
+        })
+    }
+}
+
+impl From<CspViolation> for PuppeteerError {
+    fn from(violation: CspViolation) -> Self {
+        PuppeteerError::ContentSecurityPolicyViolation {
+            directive: violation.directive,
+            blocked_uri: violation.blocked_uri,
+        }
+    }
+}
+
+#[cfg(test)]
+mod csp_checks {
+    use super::*;
+    use crate::Shell;
+
+    #[test]
+    fn builds_policy() {
+        let policy = ContentSecurityPolicy::new();
+        assert_ne!(ContentSecurityPolicy::new().nonce(), policy.nonce());
+        assert_eq!(24, policy.nonce().len());
+
+        let shell = Shell::new()
+            .add_head_links(
+                r#"<link rel="stylesheet" href="https://fonts.example.com/css?family=Inter">"#,
+            )
+            .add_head_links(r#"<link rel='icon' href=/favicon.png>"#)
+            .add_head_links(r#"<meta name="description" content="https://ignored.example.com">"#)
+            .add_script(
+                r#"<script>document.write("<script>")</script><script type="module"></script>"#
+                    .into(),
+            )
+            .with_content_security_policy(policy.clone());
+        let html = shell.to_html();
+
+        let nonce = String::from(r#"<script nonce=""#) + policy.nonce() + "\"";
+        assert_eq!(
+            html.matches("</script>").count(),
+            html.matches(&nonce).count()
+        );
+        assert!(html.contains(r#">document.write("<script>")</script>"#));
+
+        let expected = String::from(r#"<meta http-equiv="Content-Security-Policy" content=""#)
+            + "base-uri &#39;none&#39;; connect-src http://ipc.localhost ipc:; "
+            + "default-src &#39;none&#39;; font-src data:; form-action &#39;none&#39;; "
+            + "img-src &#39;self&#39; data:; script-src &#39;nonce-"
+            + policy.nonce()
+            + "&#39;; style-src &#39;unsafe-inline&#39; https://fonts.example.com\">";
+        assert!(html.contains(&expected));
+        assert!(!Shell::new().to_html().contains("http-equiv"));
+
+        let report = String::from(CSP_VIOLATION_IPC_PREFIX)
+            + policy.nonce()
+            + ">script-src-elem>https://evil.example.com/x.js";
+        assert_eq!(
+            Some(CspViolation {
+                directive: "script-src-elem".to_owned(),
+                blocked_uri: "https://evil.example.com/x.js".to_owned(),
+            }),
+            CspViolation::from_ipc(&report, policy.nonce())
+        );
+        assert_eq!(None, CspViolation::from_ipc(&report, ""));
+        assert_eq!(
+            None,
+            CspViolation::from_ipc(
+                "puppeteer:csp>script-src-elem>https://evil.example.com/x.js",
+                policy.nonce()
+            )
+        );
+        assert_eq!(
+            None,
+            CspViolation::from_ipc("puppeteer:csp>inline", policy.nonce())
+        );
+    }
+
+    #[test]
+    fn delegates_only_app_messages() {
+        let allowed = [
+            crate::ROUTE_IPC_PREFIX,
+            crate::COMPONENT_IPC_PREFIX,
+            crate::BINDING_IPC_PREFIX,
+            crate::MODAL_IPC_PREFIX,
+        ]
+        .map(|prefix| String::from("\"") + prefix + "\"")
+        .join(", ");
+        let delegated = String::from("const delegatedPrefixes = [") + &allowed + "];";
+        assert!(crate::PUPPETEER_RUNTIME_SCRIPT.contains(&delegated));
+        assert!(crate::PUPPETEER_RUNTIME_SCRIPT
+            .contains(r#"if (!message.startsWith("puppeteer:") || delegatedPrefixes"#));
+
+        [
+            crate::CLIPBOARD_IPC_PREFIX,
+            CSP_VIOLATION_IPC_PREFIX,
+            crate::SHORTCUT_IPC_PREFIX,
+            crate::DEVTOOLS_IPC_MESSAGE,
+        ]
+        .iter()
+        .for_each(|reserved| {
+            assert!(reserved.starts_with("puppeteer:"));
+            assert!(!delegated.contains(reserved));
+        });
+    }
+}
@@ -0,0 +1,359 @@
+use crate::{utils::js_string, ModifyView, PuppeteerResult, Script, Shell, UiPaint};
+use camino::{Utf8Path, Utf8PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Setting this environment variable to `1` or `true` enables the developer mode
+pub const DEV_MODE_ENV_VAR: &str = "PUPPETEER_DEV";
+
+/// The IPC message used to open or close the devtools when the developer mode is enabled.
+/// This message is never passed to `Puppeteer::parse()`
+pub const DEVTOOLS_IPC_MESSAGE: &str = "puppeteer:devtools";
+
+/// The default interval used to check whether the watched files have changed
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Opens or closes the devtools by pressing `F12` when the developer mode is enabled.
+/// `PUPPETEER_DEVTOOLS_MESSAGE` is replaced by the [DEVTOOLS_IPC_MESSAGE], see [DevMode::script]
+const DEV_MODE_SCRIPT: &str = r#"
+<script>
+document.addEventListener('keydown', (e) => {
+    if (e.key === 'F12') {
+        e.preventDefault();
+        window.ipc.postMessage(PUPPETEER_DEVTOOLS_MESSAGE);
+    }
+})
+</script>
+"#;
+
+/// The kind of file being watched
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum WatchedFileKind {
+    /// A CSS stylesheet
+    Style,
+    /// A JavaScript script with or without the `<script></script>` tags
+    Script,
+    /// A file read by the app to paint its view like an HTML template
+    View,
+}
+
+/// A file that is reloaded in the live webview when it changes on disk
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct WatchedFile {
+    /// Whether the file is a style or a script
+    pub kind: WatchedFileKind,
+    /// The path of the file
+    pub path: Utf8PathBuf,
+}
+
+/// The developer mode allows opening and closing the devtools at runtime using `F12`
+/// and reloads the styles and scripts in the live webview when their files change.
+///
+/// The developer mode is enabled by setting the [DEV_MODE_ENV_VAR] environment variable
+/// or by using [DevMode::enabled].
+/// Opening the devtools in release builds requires the `devtools` feature.
+///
+/// The files included using `static_str!()`, `load_cow!()` or `include_str!()` are compiled into
+/// the app so they should be watched using the same path, for example
+/// `concat!(env!("CARGO_MANIFEST_DIR"), "/assets/app.css")`.
+/// When a style or script changes, it is injected into the live webview without restarting
+/// the app and without painting the view again, so the state of the DOM like the values
+/// of inputs, the focus and the scroll position is kept. See [HotReload::to_script].
+/// When a view file changes, the current page of the [crate::Router] is loaded again.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DevMode {
+    enabled: bool,
+    poll_interval: Duration,
+    watched: Vec<WatchedFile>,
+}
+
+impl DevMode {
+    /// Initialize the developer mode. It is enabled if the [DEV_MODE_ENV_VAR]
+    /// environment variable is set to `1` or `true`
+    pub fn new() -> Self {
+        let enabled = std::env::var(DEV_MODE_ENV_VAR)
+            .map(|value| DevMode::is_truthy(&value))
+            .unwrap_or(false);
+
+        DevMode {
+            enabled,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            watched: Vec::default(),
+        }
+    }
+
+    /// Enable or disable the developer mode overriding the [DEV_MODE_ENV_VAR] environment variable
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+
+        self
+    }
+
+    /// Change how often the watched files are checked for changes. Default is 500 milliseconds
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+
+        self
+    }
+
+    /// Watch a CSS file
+    pub fn watch_style(mut self, path: &str) -> Self {
+        self.watched.push(WatchedFile {
+            kind: WatchedFileKind::Style,
+            path: Utf8PathBuf::from(path),
+        });
+
+        self
+    }
+
+    /// Watch a JavaScript file
+    pub fn watch_script(mut self, path: &str) -> Self {
+        self.watched.push(WatchedFile {
+            kind: WatchedFileKind::Script,
+            path: Utf8PathBuf::from(path),
+        });
+
+        self
+    }
+
+    /// Watch a file read by the app to paint its view, like an HTML template loaded at runtime.
+    /// When it changes the current page of the [crate::Router] is loaded again using
+    /// [crate::Router::reload], which keeps the state of the app. Apps without a
+    /// [crate::Router] log a warning instead since their view cannot be painted again
+    pub fn watch_view(mut self, path: &str) -> Self {
+        self.watched.push(WatchedFile {
+            kind: WatchedFileKind::View,
+            path: Utf8PathBuf::from(path),
+        });
+
+        self
+    }
+
+    /// Is the developer mode enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// The files being watched
+    pub fn watched(&self) -> &[WatchedFile] {
+        self.watched.as_slice()
+    }
+
+    /// The script added to the [crate::Shell] that opens or closes the devtools using `F12`
+    pub fn script() -> String {
+        DEV_MODE_SCRIPT.replace(
+            "PUPPETEER_DEVTOOLS_MESSAGE",
+            &js_string(DEVTOOLS_IPC_MESSAGE),
+        )
+    }
+
+    /// Name each style of the [Shell] that has the same content as a watched style file
+    /// after the path of the file so that reloading the file replaces the style,
+    /// see [Shell::name_style]
+    pub(crate) fn name_styles(&self, shell: Shell) -> Shell {
+        self.watched
+            .iter()
+            .filter(|file| file.kind == WatchedFileKind::Style)
+            .fold(shell, |shell, file| {
+                match std::fs::read_to_string(&file.path) {
+                    Ok(css) => shell.name_style(file.path.as_str(), &css),
+                    Err(error) => {
+                        tracing::warn!(
+                            "UNABLE TO READ THE WATCHED STYLE `{}`: {}",
+                            file.path,
+                            error
+                        );
+
+                        shell
+                    }
+                }
+            })
+    }
+
+    /// Poll the watched files forever calling `on_change` each time a file changes.
+    /// Stops when `on_change` returns an error.
+    pub async fn watch(
+        &self,
+        mut on_change: impl FnMut(HotReload) -> PuppeteerResult<()>,
+    ) -> PuppeteerResult<()> {
+        let mut last_modified = Vec::<Option<SystemTime>>::new();
+
+        for file in self.watched.iter() {
+            last_modified.push(DevMode::modified(&file.path).await);
+        }
+
+        loop {
+            smol::Timer::after(self.poll_interval).await;
+
+            for (file, last_modified) in self.watched.iter().zip(last_modified.iter_mut()) {
+                let modified = DevMode::modified(&file.path).await;
+
+                if modified.is_none() || modified == *last_modified {
+                    continue;
+                }
+
+                *last_modified = modified;
+
+                match smol::fs::read_to_string(&file.path).await {
+                    Ok(content) => on_change(HotReload {
+                        kind: file.kind,
+                        path: file.path.clone(),
+                        content,
+                    })?,
+                    Err(error) => tracing::warn!("UNABLE TO RELOAD `{}`: {}", file.path, error),
+                }
+            }
+        }
+    }
+
+    async fn modified(path: &Utf8Path) -> Option<SystemTime> {
+        smol::fs::metadata(path)
+            .await
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    fn is_truthy(value: &str) -> bool {
+        matches!(value.trim().to_lowercase().as_str(), "1" | "true")
+    }
+}
+
+impl Default for DevMode {
+    fn default() -> Self {
+        DevMode::new()
+    }
+}
+
+/// A watched file that changed on disk
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct HotReload {
+    /// Whether the file is a style or a script
+    pub kind: WatchedFileKind,
+    /// The path of the file
+    pub path: Utf8PathBuf,
+    /// The new content of the file
+    pub content: String,
+}
+
+impl HotReload {
+    /// The script that injects the new content into the live webview.
+    /// Styles replace the CSS of the style named after the path of the file
+    /// using [ModifyView::InjectStyle], which is the style of the [crate::Shell]
+    /// with the same content when the app started.
+    /// Scripts replace the script named after the path of the file using
+    /// [ModifyView::InjectScript] and are run again. The listeners and timers of the
+    /// previous run are not removed, so reloaded scripts must be idempotent, for example
+    /// by removing their listeners before adding them again.
+    /// View files have no script, see [HotReload::repaints_view].
+    pub fn to_script(&self) -> String {
+        let view = match self.kind {
+            WatchedFileKind::View => return String::default(),
+            WatchedFileKind::Style => ModifyView::inject_style(self.path.as_str(), &self.content),
+            WatchedFileKind::Script => ModifyView::inject_script(
+                self.path.as_str(),
+                Script::inline(HotReload::strip_script_tags(&self.content)),
+            ),
+        };
+
+        view.to_html().to_string()
+    }
+
+    /// Whether the current view has to be painted again because the file is a view file
+    pub fn repaints_view(&self) -> bool {
+        self.kind == WatchedFileKind::View
+    }
+
+    /// Scripts added to the [crate::Shell] usually include the `<script></script>` tags
+    fn strip_script_tags(content: &str) -> &str {
+        let trimmed = content.trim();
+
+        if !trimmed.starts_with("<script") {
+            return content;
+        }
+
+        let start = trimmed.find('>').map(|index| index + 1).unwrap_or_default();
+        let end = trimmed.rfind("</script>").unwrap_or(trimmed.len());
+
+        trimmed.get(start..end).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod dev_mode_checks {
+    use super::*;
+
+    #[test]
+    fn hot_reload_scripts() {
+        assert!(DevMode::is_truthy("1"));
+        assert!(DevMode::is_truthy(" TRUE"));
+        assert!(!DevMode::is_truthy("0"));
+
+        let style = HotReload {
+            kind: WatchedFileKind::Style,
+            path: Utf8PathBuf::from("assets/app.css"),
+            content: "body {content: \"`\"}".to_owned(),
+        }
+        .to_script();
+
+        assert_eq!(
+            r#"window.puppeteer.injectStyle("assets/app.css","body {content: \"`\"}");"#,
+            style
+        );
+
+        let script = HotReload {
+            kind: WatchedFileKind::Script,
+            path: Utf8PathBuf::from("assets/app.js"),
+            content: "\n<script>\nconsole.log('reloaded')\n</script>\n".to_owned(),
+        }
+        .to_script();
+
+        assert_eq!(
+            r#"window.puppeteer.injectScript("assets/app.js",null,"\nconsole.log('reloaded')\n",false,false);"#,
+            script
+        );
+        let view = HotReload {
+            kind: WatchedFileKind::View,
+            path: Utf8PathBuf::from("assets/home.html"),
+            content: "<h1>Home</h1>".to_owned(),
+        };
+        assert!(view.repaints_view());
+        assert!(view.to_script().is_empty());
+        assert!(!HotReload {
+            kind: WatchedFileKind::Style,
+            ..view
+        }
+        .repaints_view());
+
+        assert!(DevMode::script().contains(r#"window.ipc.postMessage("puppeteer:devtools");"#));
+
+        let shell = Shell::new()
+            .add_style("nav {color: red;}")
+            .add_style("\n.editor {color: red;}\n")
+            .add_style("footer {color: red;}")
+            .name_style("assets/app.css", ".editor {color: red;}");
+        assert!(shell.to_html().contains(concat!(
+            r#"<style>nav {color: red;}</style>"#,
+            r#"<style data-pp-style="assets/app.css">"#,
+            "\n.editor {color: red;}\n</style><style>footer {color: red;}</style>"
+        )));
+
+        let mut dom = crate::DomSimulator::from_shell(&shell);
+        let reload = HotReload {
+            kind: WatchedFileKind::Style,
+            path: Utf8PathBuf::from("assets/app.css"),
+            content: ".editor {color: blue;}".to_owned(),
+        };
+        dom.apply(&ModifyView::inject_style(
+            reload.path.as_str(),
+            &reload.content,
+        ))
+        .unwrap();
+        let styles = dom
+            .select("style")
+            .unwrap()
+            .iter()
+            .map(|style| style.text_content())
+            .collect::<String>();
+        assert!(styles.contains(".editor {color: blue;}"));
+        assert!(!styles.contains(".editor {color: red;}"));
+    }
+}
@@ -0,0 +1,1437 @@
+use crate::{
+    utils::js_string, DomPatch, InsertPosition, ModifyView, PuppeteerError, PuppeteerResult,
+    ScriptLoading, ScriptPlacement, Target, Theme, UiPaint,
+};
+
+/// Elements that cannot have children
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements whose content is text that is not parsed as HTML
+const RAW_TEXT_ELEMENTS: [&str; 4] = ["script", "style", "textarea", "title"];
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum NodeData {
+    Root,
+    Element {
+        tag: String,
+        attributes: Vec<(String, String)>,
+    },
+    Text(String),
+    Comment(String),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Node {
+    data: NodeData,
+    parent: Option<usize>,
+    children: Vec<usize>,
+}
+
+/// A DOM tree built in Rust that applies [ModifyView] operations the same way the
+/// [crate::PUPPETEER_RUNTIME_SCRIPT] applies them in the webview, so that tests can check
+/// the rendered document instead of the generated JavaScript.
+///
+/// Form field values set by [ModifyView::SetValue] and [ModifyView::SetBinding] are stored
+/// in the `value` attribute of inputs, the text of textareas and the `selected` attribute
+/// of the options of selects.
+///
+/// #### Example
+/// ```rust
+/// use puppeteer::{DomSimulator, ModifyView, Shell, Target};
+///
+/// let mut dom = DomSimulator::from_shell(&Shell::new());
+/// dom.apply(&ModifyView::replace_app(&r#"<ul id="list"><li>One</li></ul>"#)).unwrap();
+/// dom.apply(&ModifyView::append(Target::id("list"), "<li class=\"new\">Two</li>")).unwrap();
+///
+/// assert_eq!(2, dom.select("#list > li").unwrap().len());
+/// assert_eq!("Two", dom.select_one("li.new").unwrap().unwrap().text_content());
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DomSimulator {
+    nodes: Vec<Node>,
+}
+
+impl DomSimulator {
+    /// Parse a document
+    pub fn from_html(html: &str) -> Self {
+        let mut dom = DomSimulator {
+            nodes: vec![Node {
+                data: NodeData::Root,
+                parent: None,
+                children: Vec::default(),
+            }],
+        };
+        dom.parse_into(html, 0);
+
+        dom
+    }
+
+    /// Parse the document rendered by the [crate::Shell]
+    pub fn from_shell(shell: &crate::Shell) -> Self {
+        DomSimulator::from_html(&shell.to_html())
+    }
+
+    /// The root of the document
+    pub fn root(&self) -> SimulatedNode<'_> {
+        SimulatedNode {
+            dom: self,
+            index: 0,
+        }
+    }
+
+    /// The element with the `id`
+    pub fn by_id(&self, id: &str) -> Option<SimulatedNode<'_>> {
+        self.find_id(id)
+            .map(|index| SimulatedNode { dom: self, index })
+    }
+
+    /// All the elements matching the CSS selector in document order.
+    /// Tag, `#id`, `.class`, `[attribute]` and `[attribute=value]` selectors,
+    /// the descendant and `>` combinators and selector lists are supported
+    pub fn select(&self, selector: &str) -> PuppeteerResult<Vec<SimulatedNode<'_>>> {
+        Ok(self
+            .query(selector)?
+            .into_iter()
+            .map(|index| SimulatedNode { dom: self, index })
+            .collect())
+    }
+
+    /// The first element matching the CSS selector
+    pub fn select_one(&self, selector: &str) -> PuppeteerResult<Option<SimulatedNode<'_>>> {
+        Ok(self.select(selector)?.into_iter().next())
+    }
+
+    /// Apply the operation to the document. The operations that read the document,
+    /// like [ModifyView::ComputeWithIdData], call their callbacks and return
+    /// the operations produced by the callbacks without applying them.
+    /// Operations that are not performed in the webview, like window operations, are ignored
+    pub fn apply(&mut self, view: &ModifyView) -> PuppeteerResult<Vec<ModifyView>> {
+        let mut outcome = Vec::new();
+
+        match view {
+            ModifyView::ReplaceApp(content) => {
+                if let Some(node) = self.find_id("puppeteer_app") {
+                    self.set_inner_html(node, content);
+                }
+            }
+            ModifyView::ReplaceNodeWithId { id, content } => {
+                if let Some(node) = self.find_id(id) {
+                    self.set_inner_html(node, content);
+                }
+            }
+            ModifyView::ComputeWithIdData { id, func } => {
+                let value = self.find_id(id).map(|node| self.text_of(node));
+                outcome.push(func(&DomSimulator::encode_result(value)));
+            }
+            ModifyView::ComputeInputWithIdData { id, func } => {
+                let value = self.find_id(id).map(|node| self.value_of(node));
+                outcome.push(func(&DomSimulator::encode_result(value)));
+            }
+            ModifyView::InsertAdjacent {
+                target,
+                position,
+                content,
+            } => {
+                for node in self.targets(target)? {
+                    self.insert_adjacent(node, *position, content);
+                }
+            }
+            ModifyView::RemoveNode { target } => {
+                for node in self.targets(target)? {
+                    self.detach(node);
+                }
+            }
+            ModifyView::SetAttribute {
+                target,
+                name,
+                value,
+            } => {
+                for node in self.targets(target)? {
+                    self.set_attribute(node, name, value);
+                }
+            }
+            ModifyView::RemoveAttribute { target, name } => {
+                for node in self.targets(target)? {
+                    self.remove_attribute(node, name);
+                }
+            }
+            ModifyView::AddClass { target, class } => {
+                for node in self.targets(target)? {
+                    self.update_classes(node, |classes| {
+                        if !classes.contains(class) {
+                            classes.push(class.clone());
+                        }
+                    });
+                }
+            }
+            ModifyView::RemoveClass { target, class } => {
+                for node in self.targets(target)? {
+                    self.update_classes(node, |classes| classes.retain(|name| name != class));
+                }
+            }
+            ModifyView::ToggleClass { target, class } => {
+                for node in self.targets(target)? {
+                    self.update_classes(node, |classes| {
+                        if classes.contains(class) {
+                            classes.retain(|name| name != class);
+                        } else {
+                            classes.push(class.clone());
+                        }
+                    });
+                }
+            }
+            ModifyView::SetStyle {
+                target,
+                property,
+                value,
+            } => {
+                for node in self.targets(target)? {
+                    self.set_style(node, property, value);
+                }
+            }
+            ModifyView::SetValue { target, value } => {
+                for node in self.targets(target)? {
+                    self.set_value(node, value);
+                }
+            }
+            ModifyView::SetText { target, text } => {
+                for node in self.targets(target)? {
+                    self.set_text(node, text);
+                }
+            }
+            ModifyView::SetBinding { field, value } => {
+                for node in self.preorder(0) {
+                    if self.attribute_of(node, "data-pp-bind") != Some(field.as_str()) {
+                        continue;
+                    }
+
+                    match self.tag_of(node) {
+                        Some("input") if self.attribute_of(node, "type") == Some("checkbox") => {
+                            if value == "true" {
+                                self.set_attribute(node, "checked", "");
+                            } else {
+                                self.remove_attribute(node, "checked");
+                            }
+                        }
+                        Some("input" | "textarea" | "select") => self.set_value(node, value),
+                        _ => self.set_text(node, value),
+                    }
+                }
+            }
+            ModifyView::Patch { id, patches } => {
+                if let Some(root) = self.find_id(id) {
+                    for patch in patches {
+                        self.apply_patch(root, patch)?;
+                    }
+                }
+            }
+            ModifyView::SetTheme(theme) if *theme != Theme::System => {
+                for node in self.targets(&Target::selector("html"))? {
+                    self.set_attribute(node, "data-pp-theme", theme.as_str());
+                }
+            }
+            ModifyView::SetTitle(title) => {
+                for node in self.targets(&Target::selector("title"))? {
+                    self.set_text(node, title);
+                }
+            }
+            ModifyView::InjectStyle { name, css } => {
+                let style = match self.find_named("style", "data-pp-style", name) {
+                    Some(style) => Some(style),
+                    None => self.append_element(
+                        "head",
+                        "style",
+                        vec![("data-pp-style".to_owned(), name.clone())],
+                    )?,
+                };
+
+                if let Some(style) = style {
+                    self.set_text(style, css);
+                }
+            }
+            ModifyView::InjectScript { name, script } => {
+                let mut attributes = vec![("data-pp-script".to_owned(), name.clone())];
+
+                if script.loading() == ScriptLoading::Module {
+                    attributes.push(("type".to_owned(), "module".to_owned()));
+                }
+                if let Some(url) = script.url() {
+                    attributes.push(("src".to_owned(), url.to_owned()));
+                }
+
+                let previous = self.find_named("script", "data-pp-script", name);
+                let unchanged = previous.is_some_and(|previous| {
+                    self.nodes[previous].data
+                        == NodeData::Element {
+                            tag: "script".to_owned(),
+                            attributes: attributes.clone(),
+                        }
+                        && self.text_of(previous) == script.content()
+                });
+
+                if !unchanged {
+                    if let Some(previous) = previous {
+                        self.detach(previous);
+                    }
+
+                    let parent = match script.placement() {
+                        ScriptPlacement::Head => "head",
+                        ScriptPlacement::BodyEnd => "body",
+                    };
+
+                    if let Some(node) = self.append_element(parent, "script", attributes)? {
+                        self.set_text(node, script.content());
+                    }
+                }
+            }
+            ModifyView::RemoveStyle(name) => {
+                if let Some(style) = self.find_named("style", "data-pp-style", name) {
+                    self.detach(style);
+                }
+            }
+            ModifyView::RemoveScript(name) => {
+                if let Some(script) = self.find_named("script", "data-pp-script", name) {
+                    self.detach(script);
+                }
+            }
+            ModifyView::VirtualRows { id, rows, .. } => {
+                if let Some(node) = self.find_id(&(id.clone() + "-rows")) {
+                    self.set_inner_html(node, rows);
+                }
+            }
+            ModifyView::Toast(toast) => {
+                if let Some(node) = self.find_id("pp-toasts") {
+                    self.insert_adjacent(node, InsertPosition::BeforeEnd, &toast.to_html());
+                }
+            }
+            ModifyView::Batch(views) => {
+                for view in views {
+                    outcome.extend(self.apply(view)?);
+                }
+            }
+            _ => (),
+        }
+
+        Ok(outcome)
+    }
+
+    /// The webview passes the results to the callbacks as JSON
+    fn encode_result(value: Option<String>) -> String {
+        value.map_or_else(|| String::from("null"), |value| js_string(&value))
+    }
+
+    fn targets(&self, target: &Target) -> PuppeteerResult<Vec<usize>> {
+        match target {
+            Target::Id(id) => Ok(self.find_id(id).into_iter().collect()),
+            Target::Selector(selector) => self.query(selector),
+        }
+    }
+
+    fn apply_patch(&mut self, root: usize, patch: &DomPatch) -> PuppeteerResult<()> {
+        let at = |dom: &DomSimulator, path: &[usize]| {
+            dom.nodes[root]
+                .children
+                .first()
+                .and_then(|first| {
+                    path.iter().try_fold(*first, |node, index| {
+                        dom.nodes[node].children.get(*index).copied()
+                    })
+                })
+                .ok_or_else(|| {
+                    PuppeteerError::SimulatedDom(format!("No node at the patch path {path:?}"))
+                })
+        };
+
+        match patch {
+            DomPatch::Replace { path, node } => {
+                let old = at(self, path)?;
+                let new = self.parse_node(&node.to_html());
+                self.insert_before(old, new);
+                self.detach(old);
+            }
+            DomPatch::SetText { path, text } => {
+                let node = at(self, path)?;
+                self.nodes[node].data = NodeData::Text(text.clone());
+            }
+            DomPatch::SetAttribute { path, name, value } => {
+                let node = at(self, path)?;
+                self.set_attribute(node, name, value);
+            }
+            DomPatch::RemoveAttribute { path, name } => {
+                let node = at(self, path)?;
+                self.remove_attribute(node, name);
+            }
+            DomPatch::Insert { path, index, node } => {
+                let parent = at(self, path)?;
+                let new = self.parse_node(&node.to_html());
+                self.insert_child(parent, *index, new);
+            }
+            DomPatch::Remove { path } => {
+                let node = at(self, path)?;
+                self.detach(node);
+            }
+            DomPatch::Move { path, from, to } => {
+                let parent = at(self, path)?;
+                let children = &self.nodes[parent].children;
+                let (Some(node), Some(before)) = (children.get(*from), children.get(*to)) else {
+                    return Err(PuppeteerError::SimulatedDom(format!(
+                        "Unable to move the child {from} to {to} at the patch path {path:?}"
+                    )));
+                };
+                let (node, before) = (*node, *before);
+
+                self.detach(node);
+                self.insert_before(before, node);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn insert_adjacent(&mut self, node: usize, position: InsertPosition, html: &str) {
+        let fragment = self.parse_fragment(html);
+
+        match position {
+            InsertPosition::BeforeBegin => {
+                if self.nodes[node].parent.is_some() {
+                    fragment
+                        .into_iter()
+                        .for_each(|child| self.insert_before(node, child));
+                }
+            }
+            InsertPosition::AfterBegin => fragment
+                .into_iter()
+                .enumerate()
+                .for_each(|(index, child)| self.insert_child(node, index, child)),
+            InsertPosition::BeforeEnd => fragment
+                .into_iter()
+                .for_each(|child| self.insert_child(node, usize::MAX, child)),
+            InsertPosition::AfterEnd => {
+                if let Some(parent) = self.nodes[node].parent {
+                    let index = self.child_index(node) + 1;
+
+                    fragment
+                        .into_iter()
+                        .enumerate()
+                        .for_each(|(offset, child)| {
+                            self.insert_child(parent, index + offset, child)
+                        });
+                }
+            }
+        }
+    }
+
+    fn set_inner_html(&mut self, node: usize, html: &str) {
+        self.clear_children(node);
+
+        if RAW_TEXT_ELEMENTS.contains(&self.tag_of(node).unwrap_or_default()) {
+            let text = self.push_node(NodeData::Text(html.to_owned()));
+            self.insert_child(node, usize::MAX, text);
+        } else {
+            self.parse_into(html, node);
+        }
+    }
+
+    fn set_text(&mut self, node: usize, text: &str) {
+        self.clear_children(node);
+
+        if !text.is_empty() {
+            let text = self.push_node(NodeData::Text(text.to_owned()));
+            self.insert_child(node, usize::MAX, text);
+        }
+    }
+
+    fn set_value(&mut self, node: usize, value: &str) {
+        match self.tag_of(node) {
+            Some("textarea") => self.set_text(node, value),
+            Some("select") => {
+                for option in self.preorder(node) {
+                    if self.tag_of(option) != Some("option") {
+                        continue;
+                    }
+
+                    if self.value_of(option) == value {
+                        self.set_attribute(option, "selected", "");
+                    } else {
+                        self.remove_attribute(option, "selected");
+                    }
+                }
+            }
+            _ => self.set_attribute(node, "value", value),
+        }
+    }
+
+    fn set_style(&mut self, node: usize, property: &str, value: &str) {
+        let mut declarations = self
+            .attribute_of(node, "style")
+            .unwrap_or_default()
+            .split(';')
+            .filter_map(|declaration| {
+                let (name, value) = declaration.split_once(':')?;
+
+                Some((name.trim().to_owned(), value.trim().to_owned()))
+            })
+            .filter(|(name, _)| !name.is_empty())
+            .collect::<Vec<(String, String)>>();
+
+        match declarations.iter_mut().find(|(name, _)| name == property) {
+            Some(declaration) => declaration.1 = value.to_owned(),
+            None => declarations.push((property.to_owned(), value.to_owned())),
+        }
+
+        declarations.retain(|(_, value)| !value.is_empty());
+
+        let style = declarations
+            .iter()
+            .map(|(name, value)| name.clone() + ": " + value + ";")
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        self.set_attribute(node, "style", &style);
+    }
+
+    fn update_classes(&mut self, node: usize, update: impl FnOnce(&mut Vec<String>)) {
+        let mut classes = self
+            .attribute_of(node, "class")
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(str::to_owned)
+            .collect::<Vec<String>>();
+
+        update(&mut classes);
+        self.set_attribute(node, "class", &classes.join(" "));
+    }
+
+    fn set_attribute(&mut self, node: usize, name: &str, value: &str) {
+        if let NodeData::Element { attributes, .. } = &mut self.nodes[node].data {
+            let name = name.to_lowercase();
+
+            match attributes.iter_mut().find(|(current, _)| *current == name) {
+                Some(attribute) => attribute.1 = value.to_owned(),
+                None => attributes.push((name, value.to_owned())),
+            }
+        }
+    }
+
+    fn remove_attribute(&mut self, node: usize, name: &str) {
+        if let NodeData::Element { attributes, .. } = &mut self.nodes[node].data {
+            let name = name.to_lowercase();
+            attributes.retain(|(current, _)| *current != name);
+        }
+    }
+
+    fn tag_of(&self, node: usize) -> Option<&str> {
+        match &self.nodes[node].data {
+            NodeData::Element { tag, .. } => Some(tag.as_str()),
+            _ => None,
+        }
+    }
+
+    fn attribute_of(&self, node: usize, name: &str) -> Option<&str> {
+        match &self.nodes[node].data {
+            NodeData::Element { attributes, .. } => attributes
+                .iter()
+                .find(|(current, _)| current == name)
+                .map(|(_, value)| value.as_str()),
+            _ => None,
+        }
+    }
+
+    fn text_of(&self, node: usize) -> String {
+        match &self.nodes[node].data {
+            NodeData::Text(text) => text.clone(),
+            NodeData::Comment(_) => String::new(),
+            _ => self.nodes[node]
+                .children
+                .iter()
+                .map(|child| self.text_of(*child))
+                .collect(),
+        }
+    }
+
+    fn value_of(&self, node: usize) -> String {
+        match self.tag_of(node) {
+            Some("textarea") => self.text_of(node),
+            Some("select") => self
+                .preorder(node)
+                .into_iter()
+                .filter(|option| self.tag_of(*option) == Some("option"))
+                .find(|option| self.attribute_of(*option, "selected").is_some())
+                .map(|option| self.value_of(option))
+                .unwrap_or_default(),
+            Some("option") => self
+                .attribute_of(node, "value")
+                .map(str::to_owned)
+                .unwrap_or_else(|| self.text_of(node)),
+            _ => self
+                .attribute_of(node, "value")
+                .unwrap_or_default()
+                .to_owned(),
+        }
+    }
+
+    fn find_id(&self, id: &str) -> Option<usize> {
+        self.preorder(0)
+            .into_iter()
+            .find(|node| self.attribute_of(*node, "id") == Some(id))
+    }
+
+    /// The element added by the runtime with the `name` in the `attribute`
+    fn find_named(&self, tag: &str, attribute: &str, name: &str) -> Option<usize> {
+        self.preorder(0).into_iter().find(|node| {
+            self.tag_of(*node) == Some(tag) && self.attribute_of(*node, attribute) == Some(name)
+        })
+    }
+
+    /// Create an element at the end of the first node matching the `parent` selector
+    fn append_element(
+        &mut self,
+        parent: &str,
+        tag: &str,
+        attributes: Vec<(String, String)>,
+    ) -> PuppeteerResult<Option<usize>> {
+        let Some(parent) = self.query(parent)?.into_iter().next() else {
+            return Ok(None);
+        };
+
+        let node = self.push_node(NodeData::Element {
+            tag: tag.to_owned(),
+            attributes,
+        });
+        self.insert_child(parent, usize::MAX, node);
+
+        Ok(Some(node))
+    }
+
+    /// The descendants of the node in document order, without the node itself
+    fn preorder(&self, node: usize) -> Vec<usize> {
+        let mut outcome = Vec::new();
+        let mut stack = self.nodes[node]
+            .children
+            .iter()
+            .rev()
+            .copied()
+            .collect::<Vec<usize>>();
+
+        while let Some(current) = stack.pop() {
+            outcome.push(current);
+            stack.extend(self.nodes[current].children.iter().rev());
+        }
+
+        outcome
+    }
+
+    fn push_node(&mut self, data: NodeData) -> usize {
+        self.nodes.push(Node {
+            data,
+            parent: None,
+            children: Vec::default(),
+        });
+
+        self.nodes.len() - 1
+    }
+
+    fn child_index(&self, node: usize) -> usize {
+        self.nodes[node]
+            .parent
+            .and_then(|parent| {
+                self.nodes[parent]
+                    .children
+                    .iter()
+                    .position(|child| *child == node)
+            })
+            .unwrap_or_default()
+    }
+
+    /// Insert the child at the index, or last if the index is past the last child
+    fn insert_child(&mut self, parent: usize, index: usize, child: usize) {
+        self.detach(child);
+
+        let children = &mut self.nodes[parent].children;
+        children.insert(index.min(children.len()), child);
+        self.nodes[child].parent = Some(parent);
+    }
+
+    fn insert_before(&mut self, reference: usize, child: usize) {
+        if let Some(parent) = self.nodes[reference].parent {
+            self.detach(child);
+            let index = self.child_index(reference);
+            self.insert_child(parent, index, child);
+        }
+    }
+
+    fn detach(&mut self, node: usize) {
+        if let Some(parent) = self.nodes[node].parent.take() {
+            self.nodes[parent].children.retain(|child| *child != node);
+        }
+    }
+
+    fn clear_children(&mut self, node: usize) {
+        for child in std::mem::take(&mut self.nodes[node].children) {
+            self.nodes[child].parent = None;
+        }
+    }
+
+    /// Parse the HTML into nodes that are not attached to the document
+    fn parse_fragment(&mut self, html: &str) -> Vec<usize> {
+        let container = self.push_node(NodeData::Root);
+        self.parse_into(html, container);
+
+        let children = self.nodes[container].children.clone();
+        self.clear_children(container);
+
+        children
+    }
+
+    /// Parse the first node of the HTML like the `template` element used by the runtime
+    fn parse_node(&mut self, html: &str) -> usize {
+        self.parse_fragment(html)
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| self.push_node(NodeData::Text(String::new())))
+    }
+
+    fn parse_into(&mut self, html: &str, parent: usize) {
+        let mut open = vec![parent];
+        let mut rest = html;
+
+        while !rest.is_empty() {
+            let current = *open.last().unwrap_or(&parent);
+
+            if let Some(comment) = rest.strip_prefix("<!--") {
+                let end = comment.find("-->").unwrap_or(comment.len());
+                let node = self.push_node(NodeData::Comment(comment[..end].to_owned()));
+                self.insert_child(current, usize::MAX, node);
+                rest = comment.get(end + 3..).unwrap_or_default();
+            } else if rest.starts_with("<!") || rest.starts_with("<?") {
+                rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            } else if let Some(closing) = rest.strip_prefix("</") {
+                let end = closing.find('>').unwrap_or(closing.len());
+                let tag = closing[..end].trim().to_lowercase();
+
+                if let Some(position) = open
+                    .iter()
+                    .skip(1)
+                    .rposition(|node| self.tag_of(*node) == Some(tag.as_str()))
+                {
+                    open.truncate(position + 1);
+                }
+
+                rest = closing.get(end + 1..).unwrap_or_default();
+            } else if rest.starts_with('<')
+                && rest[1..].starts_with(|character: char| character.is_ascii_alphabetic())
+            {
+                let (tag, attributes, self_closing, remaining) =
+                    DomSimulator::parse_tag(&rest[1..]);
+                let node = self.push_node(NodeData::Element {
+                    tag: tag.clone(),
+                    attributes,
+                });
+                self.insert_child(current, usize::MAX, node);
+                rest = remaining;
+
+                if RAW_TEXT_ELEMENTS.contains(&tag.as_str()) {
+                    let closing = String::from("</") + &tag;
+                    let end = rest
+                        .to_ascii_lowercase()
+                        .find(&closing)
+                        .unwrap_or(rest.len());
+                    let content = &rest[..end];
+
+                    if !content.is_empty() {
+                        let content = if tag == "script" || tag == "style" {
+                            content.to_owned()
+                        } else {
+                            decode_entities(content)
+                        };
+                        let text = self.push_node(NodeData::Text(content));
+                        self.insert_child(node, usize::MAX, text);
+                    }
+
+                    rest = rest[end..]
+                        .find('>')
+                        .map_or("", |index| &rest[end + index + 1..]);
+                } else if !self_closing && !VOID_ELEMENTS.contains(&tag.as_str()) {
+                    open.push(node);
+                }
+            } else {
+                let end = rest
+                    .char_indices()
+                    .skip(1)
+                    .find(|(_, character)| *character == '<')
+                    .map_or(rest.len(), |(index, _)| index);
+                let text = decode_entities(&rest[..end]);
+                rest = &rest[end..];
+
+                let previous = self.nodes[current].children.last().copied();
+
+                match previous.map(|previous| &mut self.nodes[previous].data) {
+                    Some(NodeData::Text(previous)) => previous.push_str(&text),
+                    _ => {
+                        let node = self.push_node(NodeData::Text(text));
+                        self.insert_child(current, usize::MAX, node);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Parses a start tag without the `<`, returning the remaining HTML after the `>`
+    fn parse_tag(html: &str) -> (String, Vec<(String, String)>, bool, &str) {
+        let is_name_end =
+            |character: char| character.is_whitespace() || character == '/' || character == '>';
+        let end = html.find(is_name_end).unwrap_or(html.len());
+        let tag = html[..end].to_lowercase();
+        let mut rest = &html[end..];
+        let mut attributes = Vec::<(String, String)>::new();
+
+        loop {
+            rest = rest.trim_start();
+
+            if let Some(remaining) = rest.strip_prefix("/>") {
+                return (tag, attributes, true, remaining);
+            }
+
+            if rest.is_empty() {
+                return (tag, attributes, false, rest);
+            }
+
+            if let Some(remaining) = rest.strip_prefix('>') {
+                return (tag, attributes, false, remaining);
+            }
+
+            if let Some(remaining) = rest.strip_prefix('/') {
+                rest = remaining;
+                continue;
+            }
+
+            let end = rest
+                .find(|character: char| is_name_end(character) || character == '=')
+                .unwrap_or(rest.len());
+            let name = rest[..end].to_lowercase();
+            rest = rest[end..].trim_start();
+
+            let value = match rest.strip_prefix('=') {
+                Some(remaining) => {
+                    let remaining = remaining.trim_start();
+
+                    match remaining.chars().next() {
+                        Some(quote @ ('"' | '\'')) => {
+                            let inner = &remaining[1..];
+                            let end = inner.find(quote).unwrap_or(inner.len());
+                            rest = inner.get(end + 1..).unwrap_or_default();
+
+                            decode_entities(&inner[..end])
+                        }
+                        _ => {
+                            let end = remaining
+                                .find(|character: char| {
+                                    character.is_whitespace() || character == '>'
+                                })
+                                .unwrap_or(remaining.len());
+                            rest = &remaining[end..];
+
+                            decode_entities(&remaining[..end])
+                        }
+                    }
+                }
+                None => String::new(),
+            };
+
+            if !name.is_empty() && !attributes.iter().any(|(current, _)| *current == name) {
+                attributes.push((name, value));
+            }
+        }
+    }
+
+    fn query(&self, selector: &str) -> PuppeteerResult<Vec<usize>> {
+        let selectors = selector
+            .split(',')
+            .map(Selector::parse)
+            .collect::<PuppeteerResult<Vec<Selector>>>()?;
+
+        Ok(self
+            .preorder(0)
+            .into_iter()
+            .filter(|node| {
+                selectors
+                    .iter()
+                    .any(|selector| selector.matches(self, *node))
+            })
+            .collect())
+    }
+
+    fn write_html(&self, node: usize, outcome: &mut String) {
+        match &self.nodes[node].data {
+            NodeData::Root => self.write_children(node, outcome),
+            NodeData::Text(text) => {
+                let parent = self.nodes[node]
+                    .parent
+                    .and_then(|parent| self.tag_of(parent));
+
+                if matches!(parent, Some("script" | "style")) {
+                    outcome.push_str(text);
+                } else {
+                    outcome.push_str(
+                        &text
+                            .replace('&', "&amp;")
+                            .replace('<', "&lt;")
+                            .replace('>', "&gt;"),
+                    );
+                }
+            }
+            NodeData::Comment(comment) => {
+                outcome.push_str("<!--");
+                outcome.push_str(comment);
+                outcome.push_str("-->");
+            }
+            NodeData::Element { tag, attributes } => {
+                outcome.push('<');
+                outcome.push_str(tag);
+
+                for (name, value) in attributes {
+                    outcome.push(' ');
+                    outcome.push_str(name);
+                    outcome.push_str("=\"");
+                    outcome.push_str(&value.replace('&', "&amp;").replace('"', "&quot;"));
+                    outcome.push('"');
+                }
+
+                outcome.push('>');
+
+                if !VOID_ELEMENTS.contains(&tag.as_str()) {
+                    self.write_children(node, outcome);
+                    outcome.push_str("</");
+                    outcome.push_str(tag);
+                    outcome.push('>');
+                }
+            }
+        }
+    }
+
+    fn write_children(&self, node: usize, outcome: &mut String) {
+        self.nodes[node]
+            .children
+            .iter()
+            .for_each(|child| self.write_html(*child, outcome));
+    }
+}
+
+/// A node of a [DomSimulator] document
+#[derive(Debug, Clone, Copy)]
+pub struct SimulatedNode<'a> {
+    dom: &'a DomSimulator,
+    index: usize,
+}
+
+impl<'a> SimulatedNode<'a> {
+    /// The lowercase tag name if the node is an element
+    pub fn tag(&self) -> Option<&'a str> {
+        self.dom.tag_of(self.index)
+    }
+
+    /// Is the node a text node
+    pub fn is_text(&self) -> bool {
+        matches!(self.dom.nodes[self.index].data, NodeData::Text(_))
+    }
+
+    /// The value of an attribute
+    pub fn attribute(&self, name: &str) -> Option<&'a str> {
+        self.dom.attribute_of(self.index, &name.to_lowercase())
+    }
+
+    /// The attributes in the order they were added
+    pub fn attributes(&self) -> Vec<(&'a str, &'a str)> {
+        match &self.dom.nodes[self.index].data {
+            NodeData::Element { attributes, .. } => attributes
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str()))
+                .collect(),
+            _ => Vec::default(),
+        }
+    }
+
+    /// Does the `class` attribute contain the class
+    pub fn has_class(&self, class: &str) -> bool {
+        self.attribute("class")
+            .unwrap_or_default()
+            .split_whitespace()
+            .any(|name| name == class)
+    }
+
+    /// The text of the node and its descendants
+    pub fn text_content(&self) -> String {
+        self.dom.text_of(self.index)
+    }
+
+    /// The value of a form field
+    pub fn value(&self) -> String {
+        self.dom.value_of(self.index)
+    }
+
+    /// The child nodes including text and comment nodes
+    pub fn child_nodes(&self) -> Vec<SimulatedNode<'a>> {
+        self.dom.nodes[self.index]
+            .children
+            .iter()
+            .map(|index| SimulatedNode {
+                dom: self.dom,
+                index: *index,
+            })
+            .collect()
+    }
+
+    /// The child elements
+    pub fn children(&self) -> Vec<SimulatedNode<'a>> {
+        self.child_nodes()
+            .into_iter()
+            .filter(|child| child.tag().is_some())
+            .collect()
+    }
+
+    /// The parent node
+    pub fn parent(&self) -> Option<SimulatedNode<'a>> {
+        self.dom.nodes[self.index]
+            .parent
+            .map(|index| SimulatedNode {
+                dom: self.dom,
+                index,
+            })
+    }
+
+    /// The HTML of the child nodes
+    pub fn inner_html(&self) -> String {
+        let mut outcome = String::new();
+        self.dom.write_children(self.index, &mut outcome);
+
+        outcome
+    }
+
+    /// The HTML of the node including the node itself
+    pub fn outer_html(&self) -> String {
+        let mut outcome = String::new();
+        self.dom.write_html(self.index, &mut outcome);
+
+        outcome
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+struct CompoundSelector {
+    tag: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    attributes: Vec<(String, Option<String>)>,
+}
+
+impl CompoundSelector {
+    fn matches(&self, dom: &DomSimulator, node: usize) -> bool {
+        let Some(tag) = dom.tag_of(node) else {
+            return false;
+        };
+        let class = dom.attribute_of(node, "class").unwrap_or_default();
+
+        self.tag.as_ref().is_none_or(|expected| expected == tag)
+            && self
+                .id
+                .as_ref()
+                .is_none_or(|id| dom.attribute_of(node, "id") == Some(id.as_str()))
+            && self
+                .classes
+                .iter()
+                .all(|expected| class.split_whitespace().any(|name| name == expected))
+            && self.attributes.iter().all(|(name, expected)| {
+                match (dom.attribute_of(node, name), expected) {
+                    (Some(value), Some(expected)) => value == expected,
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                }
+            })
+    }
+}
+
+/// A complex selector stored from right to left with the combinator to the previous compound
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+struct Selector {
+    parts: Vec<(CompoundSelector, bool)>,
+}
+
+impl Selector {
+    fn parse(selector: &str) -> PuppeteerResult<Self> {
+        let invalid = || PuppeteerError::SimulatedDom(format!("Unsupported selector `{selector}`"));
+        let mut parts = Vec::<(CompoundSelector, bool)>::new();
+        let mut compound = CompoundSelector::default();
+        let mut is_empty = true;
+        let mut child = false;
+        let mut chars = selector.trim().chars().peekable();
+
+        let name = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+            let mut name = String::new();
+
+            while let Some(character) = chars.peek() {
+                if character.is_alphanumeric() || matches!(character, '-' | '_') {
+                    name.push(*character);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+
+            name
+        };
+
+        while let Some(character) = chars.peek().copied() {
+            match character {
+                ' ' | '>' => {
+                    while matches!(chars.peek(), Some(' ' | '>')) {
+                        child |= chars.next() == Some('>');
+                    }
+
+                    if is_empty {
+                        return Err(invalid());
+                    }
+
+                    parts.push((std::mem::take(&mut compound), child));
+                    is_empty = true;
+                    child = false;
+                }
+                '#' | '.' => {
+                    chars.next();
+                    let value = name(&mut chars);
+
+                    if value.is_empty() {
+                        return Err(invalid());
+                    }
+
+                    if character == '#' {
+                        compound.id = Some(value);
+                    } else {
+                        compound.classes.push(value);
+                    }
+                    is_empty = false;
+                }
+                '[' => {
+                    chars.next();
+                    let mut inner = String::new();
+
+                    for character in chars.by_ref() {
+                        if character == ']' {
+                            break;
+                        }
+                        inner.push(character);
+                    }
+
+                    let attribute = match inner.split_once('=') {
+                        Some((name, value)) => (
+                            name.trim().to_lowercase(),
+                            Some(value.trim().trim_matches(['"', '\'']).to_owned()),
+                        ),
+                        None => (inner.trim().to_lowercase(), None),
+                    };
+
+                    if attribute.0.is_empty() {
+                        return Err(invalid());
+                    }
+
+                    compound.attributes.push(attribute);
+                    is_empty = false;
+                }
+                '*' => {
+                    chars.next();
+                    is_empty = false;
+                }
+                _ => {
+                    let tag = name(&mut chars);
+
+                    if tag.is_empty() {
+                        return Err(invalid());
+                    }
+
+                    compound.tag = Some(tag.to_lowercase());
+                    is_empty = false;
+                }
+            }
+        }
+
+        if is_empty {
+            return Err(invalid());
+        }
+
+        parts.push((compound, false));
+
+        // Each compound stores whether its match must be the child of the compound on its left
+        let mut combinators = parts.iter().map(|(_, child)| *child).collect::<Vec<bool>>();
+        combinators.rotate_right(1);
+
+        Ok(Selector {
+            parts: parts
+                .into_iter()
+                .map(|(compound, _)| compound)
+                .zip(combinators)
+                .rev()
+                .collect(),
+        })
+    }
+
+    fn matches(&self, dom: &DomSimulator, node: usize) -> bool {
+        self.matches_from(dom, node, 0)
+    }
+
+    fn matches_from(&self, dom: &DomSimulator, node: usize, part: usize) -> bool {
+        let Some((compound, _)) = self.parts.get(part) else {
+            return true;
+        };
+
+        if !compound.matches(dom, node) {
+            return false;
+        }
+
+        if part + 1 == self.parts.len() {
+            return true;
+        }
+
+        let child = self.parts[part].1;
+        let mut ancestor = dom.nodes[node].parent;
+
+        while let Some(current) = ancestor {
+            if self.matches_from(dom, current, part + 1) {
+                return true;
+            }
+
+            if child {
+                return false;
+            }
+
+            ancestor = dom.nodes[current].parent;
+        }
+
+        false
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_owned();
+    }
+
+    let mut outcome = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        outcome.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let character = match &rest[1..end] {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                entity => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| entity.strip_prefix('#').map(str::parse))
+                    .and_then(Result::ok)
+                    .and_then(char::from_u32),
+            }?;
+
+            Some((character, end))
+        });
+
+        match decoded {
+            Some((character, end)) => {
+                outcome.push(character);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                outcome.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    outcome.push_str(rest);
+
+    outcome
+}
+
+#[cfg(test)]
+mod dom_simulator_checks {
+    use super::*;
+    use crate::{Html, Shell, VDom};
+
+    fn counter(value: &str) -> ModifyView {
+        ModifyView::set_text(Target::id("count"), &(value.to_owned() + "!"))
+    }
+
+    #[test]
+    fn parses_the_shell() {
+        let dom = DomSimulator::from_shell(&Shell::new());
+
+        assert_eq!(Some("div"), dom.by_id("puppeteer_app").unwrap().tag());
+        assert_eq!(1, dom.select("head > script").unwrap().len());
+        assert!(dom
+            .select_one("head script")
+            .unwrap()
+            .unwrap()
+            .text_content()
+            .contains("window.puppeteer"));
+        assert!(dom.select("div >").is_err());
+    }
+
+    #[test]
+    fn applies_operations() {
+        let mut dom = DomSimulator::from_shell(&Shell::new());
+
+        dom.apply(&ModifyView::replace_app(&concat!(
+            r#"<main class="page"><p id="count">1 &amp; 2</p>"#,
+            r#"<input id="email" value='a@b'><ul id="list"><li data-key=a>A</li></ul>"#,
+            r#"<select data-pp-bind="size"><option>S</option><option value="l">L</option></select></main>"#
+        )))
+        .unwrap();
+
+        assert_eq!("1 & 2", dom.by_id("count").unwrap().text_content());
+
+        let batch = ModifyView::batch([
+            ModifyView::append(Target::selector("main.page > ul"), "<li>B</li>"),
+            ModifyView::insert_adjacent(Target::id("list"), InsertPosition::BeforeBegin, "<hr>"),
+            ModifyView::toggle_class(Target::selector("li[data-key='a']"), "active"),
+            ModifyView::set_style(Target::id("list"), "--pp-gap", "4px"),
+            ModifyView::set_value(Target::id("email"), "<c@d>"),
+            ModifyView::SetBinding {
+                field: "size".to_owned(),
+                value: "l".to_owned(),
+            },
+            ModifyView::compute_with_data("count", counter),
+            ModifyView::compute_input_with_data("email", counter),
+            ModifyView::remove_node(Target::selector("#missing")),
+        ]);
+        let follow_ups = dom.apply(&batch).unwrap();
+
+        assert_eq!(
+            vec![counter("\"1 & 2\""), counter("\"\\u003cc@d\\u003e\"")],
+            follow_ups
+        );
+        assert_eq!(
+            r#"<li data-key="a" class="active">A</li><li>B</li>"#,
+            dom.by_id("list").unwrap().inner_html()
+        );
+        assert_eq!(
+            Some("--pp-gap: 4px;"),
+            dom.by_id("list").unwrap().attribute("style")
+        );
+        assert_eq!(
+            Some("hr"),
+            dom.by_id("email").unwrap().parent().unwrap().children()[2].tag()
+        );
+        assert_eq!("<c@d>", dom.by_id("email").unwrap().value());
+        assert_eq!("l", dom.select_one("select").unwrap().unwrap().value());
+    }
+
+    #[test]
+    fn applies_vdom_patches() {
+        let mut dom = DomSimulator::from_html(r#"<div id="mount"></div>"#);
+        let vdom = VDom::new();
+        let list = |keys: &[&str]| {
+            Html::ul().children(keys.iter().map(|key| {
+                Html::li()
+                    .key(key)
+                    .attr("title", key)
+                    .child(Html::text(key))
+            }))
+        };
+
+        for keys in [
+            &["a", "b", "c"][..],
+            &["c", "a", "d"],
+            &["d"],
+            &["b", "a", "d", "c"],
+        ] {
+            dom.apply(&vdom.render("mount", list(keys))).unwrap();
+
+            assert_eq!(
+                list(keys).to_html(),
+                dom.by_id("mount").unwrap().inner_html()
+            );
+        }
+    }
+
+    #[test]
+    fn injects_named_styles_and_scripts() {
+        let mut dom = DomSimulator::from_shell(&Shell::new());
+        let styles = |dom: &DomSimulator| dom.select("style[data-pp-style]").unwrap().len();
+        let chart = crate::Script::source("/chart.js").module().in_head();
+
+        dom.apply(&ModifyView::inject_style("editor", ".editor {color: red;}"))
+            .unwrap();
+        dom.apply(&ModifyView::inject_style(
+            "editor",
+            ".editor {color: blue;}",
+        ))
+        .unwrap();
+        assert_eq!(1, styles(&dom));
+        assert_eq!(
+            ".editor {color: blue;}",
+            dom.select_one("head > style[data-pp-style='editor']")
+                .unwrap()
+                .unwrap()
+                .text_content()
+        );
+
+        dom.apply(&ModifyView::batch([
+            ModifyView::inject_script("chart", chart.clone()),
+            ModifyView::inject_script("chart", chart.clone()),
+            ModifyView::inject_script("greet", crate::Script::inline("greet('</script>')")),
+        ]))
+        .unwrap();
+        assert_eq!(
+            Some("module"),
+            dom.select_one("head > script[data-pp-script='chart'][src='/chart.js']")
+                .unwrap()
+                .unwrap()
+                .attribute("type")
+        );
+        assert_eq!(
+            1,
+            dom.select("script[data-pp-script='chart']").unwrap().len()
+        );
+        assert_eq!(
+            "greet('</script>')",
+            dom.select_one("body > script[data-pp-script='greet']")
+                .unwrap()
+                .unwrap()
+                .text_content()
+        );
+
+        dom.apply(&ModifyView::remove_style("editor")).unwrap();
+        dom.apply(&ModifyView::remove_script("chart")).unwrap();
+        assert_eq!(0, styles(&dom));
+        assert!(dom
+            .select("script[data-pp-script='chart']")
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(
+            r#"window.puppeteer.injectStyle("editor",".a \u003e .b {}");"#,
+            ModifyView::inject_style("editor", ".a > .b {}").to_html()
+        );
+        assert_eq!(
+            r#"window.puppeteer.injectScript("chart","/chart.js","",true,true);"#,
+            ModifyView::inject_script("chart", chart).to_html()
+        );
+        assert_eq!(
+            r#"window.puppeteer.injectScript("greet",null,"greet()",false,false);"#,
+            ModifyView::inject_script("greet", crate::Script::inline("greet()")).to_html()
+        );
+        assert_eq!(
+            r#"window.puppeteer.removeScript("greet");"#,
+            ModifyView::remove_script("greet").to_html()
+        );
+    }
+}
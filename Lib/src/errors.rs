@@ -46,6 +46,20 @@ pub enum PuppeteerError {
     /// For webview only WOFF2 font format is supported.
     #[error("The font detected ({0:?}) is not a valid `WOFF2` format for the web.")]
     InvalidFontExpectedWoff2(String),
+    /// The font is not a WOFF2, WOFF, TTF or OTF file
+    #[error("The font `{name}` is a `{format}` file instead of a WOFF2, WOFF, TTF or OTF file")]
+    UnsupportedFontFormat {
+        /// The name of the font asset
+        name: String,
+        /// The format detected
+        format: String,
+    },
+    /// The tables of the font could not be read
+    #[error("Invalid font data: {0}")]
+    InvalidFontData(String),
+    /// The weight or the unicode range of a [crate::FontVariant] is invalid
+    #[error("Invalid font variant: {0}")]
+    InvalidFontVariant(String),
     /// Tried to get a file name without the extension part using `Path::file_stem()` but the file stem does not exist
     #[error("Tried to get a file name without the extension part using `Path::file_stem()` but the file stem does not exist")]
     InvalidFileStemName,
@@ -55,6 +69,81 @@ pub enum PuppeteerError {
     /// Encountered a GTK error on Linux
     #[error("Encountered a GTK error on Linux")]
     GtkError,
+    /// The clipboard is empty or its content is not in the requested format
+    #[error("The clipboard is empty or its content is not in the requested format")]
+    ClipboardContentNotAvailable,
+    /// The clipboard is not supported by the current system configuration
+    #[error("The clipboard is not supported by the current system configuration")]
+    ClipboardNotSupported,
+    /// The clipboard is being held by another process or thread
+    #[error("The clipboard is being held by another process or thread")]
+    ClipboardOccupied,
+    /// The content could not be converted to or from the format used by the clipboard
+    #[error("The content could not be converted to or from the format used by the clipboard")]
+    ClipboardConversionFailure,
+    /// An error returned by the clipboard of the operating system
+    #[error("Clipboard error: {0}")]
+    Clipboard(String),
+    /// The image is not a valid PNG image or could not be encoded as one
+    #[error("Invalid clipboard image: {0}")]
+    ClipboardImage(String),
+    /// The page tried to read the clipboard using an IPC message but reading it
+    /// using IPC is not allowed, see `Clipboard::allow_ipc_reads()`
+    #[error("Reading the {0} of the clipboard using IPC is not allowed")]
+    ClipboardIpcReadDenied(&'static str),
+    /// The IPC message uses the reserved clipboard prefix but is not a valid clipboard request
+    #[error("Invalid clipboard request `{0}`")]
+    InvalidClipboardRequest(String),
+    /// The keyboard shortcut is not a valid accelerator or is not registered
+    #[error("Invalid keyboard shortcut `{0}`")]
+    InvalidShortcut(String),
+    /// The keyboard shortcut is already bound to another event in the same scope
+    #[error("The keyboard shortcut `{0}` is already bound to another event in the same scope")]
+    ShortcutConflict(String),
+    /// The script of a `ModifyView::Batch` did not return one result per compute operation
+    #[error("The batched operations returned an invalid result `{0}`")]
+    InvalidBatchResult(String),
+    /// The mount ID of a component is empty or contains `>`
+    #[error("The component mount ID `{0}` is empty or contains `>`")]
+    InvalidComponentId(String),
+    /// An IPC message was sent to a component that is not mounted
+    #[error("No component is mounted with the ID `{0}`")]
+    ComponentNotMounted(String),
+    /// The payload of an IPC message could not be parsed by the component
+    #[error("The component could not parse the message `{0}`")]
+    InvalidComponentMessage(String),
+    /// No route of the router matches the path
+    #[error("No route matches the path `{0}`")]
+    RouteNotFound(String),
+    /// The guards redirected the navigation too many times, usually because of a redirect loop
+    #[error("Too many redirects while navigating to `{0}`")]
+    TooManyRouteRedirects(String),
+    /// The [crate::ModifyView] could not be applied to the simulated DOM
+    #[error("DOM simulator error: {0}")]
+    SimulatedDom(String),
+    /// The locale is not a valid BCP 47 language identifier like `en-US`
+    #[error("Invalid locale `{0}`")]
+    InvalidLocale(String),
+    /// The Fluent resource could not be parsed or conflicts with the messages already added
+    #[error("Invalid Fluent resource: {0}")]
+    InvalidFluentResource(String),
+    /// Rows were requested for a virtual list without a registered data source
+    #[error("No data source is registered for the virtual list `{0}`")]
+    VirtualListNotRegistered(String),
+    /// The color is not in the `#RGB`, `#RGBA`, `#RRGGBB` or `#RRGGBBAA` notation
+    #[error("Invalid hex color `{0}`")]
+    InvalidHexColor(String),
+    /// Colors of the palette used together do not have enough contrast to be readable
+    #[error("Insufficient contrast: {0}")]
+    InsufficientContrast(String),
+    /// The webview blocked a resource because of the [crate::ContentSecurityPolicy]
+    #[error("The Content-Security-Policy directive `{directive}` blocked `{blocked_uri}`")]
+    ContentSecurityPolicyViolation {
+        /// The directive that blocked the resource like `script-src`
+        directive: String,
+        /// The URI of the resource or `inline` and `eval` for inline scripts and `eval()`
+        blocked_uri: String,
+    },
 }
 
 impl From<std::io::Error> for PuppeteerError {
@@ -81,6 +170,18 @@ impl From<WryError> for PuppeteerError {
     }
 }
 
+impl From<arboard::Error> for PuppeteerError {
+    fn from(value: arboard::Error) -> Self {
+        match value {
+            arboard::Error::ContentNotAvailable => PuppeteerError::ClipboardContentNotAvailable,
+            arboard::Error::ClipboardNotSupported => PuppeteerError::ClipboardNotSupported,
+            arboard::Error::ClipboardOccupied => PuppeteerError::ClipboardOccupied,
+            arboard::Error::ConversionFailure => PuppeteerError::ClipboardConversionFailure,
+            _ => PuppeteerError::Clipboard(value.to_string()),
+        }
+    }
+}
+
 impl From<OsError> for PuppeteerError {
     fn from(value: OsError) -> Self {
         PuppeteerError::TaoOsError(value.to_string())
@@ -0,0 +1,589 @@
+use crate::{FontStyle, PuppeteerError, PuppeteerResult};
+use std::{borrow::Cow, io::Read, ops::RangeInclusive};
+
+/// The maximum size of the decompressed tables of a WOFF or WOFF2 font
+pub const MAX_DECOMPRESSED_FONT_SIZE: usize = 64 * 1024 * 1024;
+
+/// The tags of the known tables of a WOFF2 table directory, in the order of the specification
+const WOFF2_KNOWN_TAGS: [&[u8; 4]; 63] = [
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post", b"cvt ", b"fpgm",
+    b"glyf", b"loca", b"prep", b"CFF ", b"VORG", b"EBDT", b"EBLC", b"gasp", b"hdmx", b"kern",
+    b"LTSH", b"PCLT", b"VDMX", b"vhea", b"vmtx", b"BASE", b"GDEF", b"GPOS", b"GSUB", b"EBSC",
+    b"JSTF", b"MATH", b"CBDT", b"CBLC", b"COLR", b"CPAL", b"SVG ", b"sbix", b"acnt", b"avar",
+    b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar", b"gvar", b"hsty",
+    b"just", b"lcar", b"mort", b"morx", b"opbd", b"prop", b"trak", b"Zapf", b"Silf", b"Glat",
+    b"Gloc", b"Feat", b"Sill",
+];
+
+/// The tables read to build the [FontMetadata]
+const METADATA_TAGS: [&[u8; 4]; 3] = [b"name", b"OS/2", b"cmap"];
+
+/// The family, weight, style and characters of a font, read from the `name`, `OS/2`
+/// and `cmap` tables of a TTF, OTF, WOFF or WOFF2 file
+///
+/// #### Example
+/// ```rust,ignore
+/// use puppeteer::FontMetadata;
+///
+/// let metadata = FontMetadata::parse(include_bytes!("../assets/Inter-Bold.woff2"))?;
+/// assert_eq!("Inter", metadata.family());
+/// assert_eq!(700, metadata.weight());
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FontMetadata {
+    family: String,
+    subfamily: String,
+    weight: u16,
+    style: FontStyle,
+    unicode_ranges: Vec<RangeInclusive<u32>>,
+}
+
+impl FontMetadata {
+    /// Parse the tables of the font, decompressing WOFF and WOFF2 files
+    pub fn parse(bytes: &[u8]) -> PuppeteerResult<Self> {
+        let tables = FontTables::parse(bytes)?;
+
+        let name = tables
+            .get(b"name")
+            .ok_or_else(|| invalid_font("The font has no `name` table"))?;
+        let family = FontMetadata::name(name, &[16, 1])
+            .ok_or_else(|| invalid_font("The font has no family name"))?;
+        let subfamily = FontMetadata::name(name, &[17, 2]).unwrap_or_else(|| "Regular".to_owned());
+
+        let (weight, style) = match tables.get(b"OS/2") {
+            Some(os2) => {
+                let weight = match read_u16(os2, 4).unwrap_or(400) {
+                    0 => 400,
+                    // Some old fonts use the values from 1 to 9
+                    weight @ 1..=9 => weight * 100,
+                    weight => weight.min(1000),
+                };
+                let selection = read_u16(os2, 62).unwrap_or_default();
+                let style = if selection & 1 != 0 {
+                    FontStyle::Italic
+                } else if selection & (1 << 9) != 0 {
+                    FontStyle::Oblique
+                } else {
+                    FontStyle::Normal
+                };
+
+                (weight, style)
+            }
+            None => (400, FontMetadata::style_from_name(&subfamily)),
+        };
+
+        let unicode_ranges = tables
+            .get(b"cmap")
+            .map(|cmap| FontMetadata::unicode_ranges_from_cmap(cmap))
+            .unwrap_or_default();
+
+        Ok(FontMetadata {
+            family,
+            subfamily,
+            weight,
+            style,
+            unicode_ranges,
+        })
+    }
+
+    /// The family, like `Inter`
+    pub fn family(&self) -> &str {
+        self.family.as_str()
+    }
+
+    /// The subfamily, like `Bold Italic`
+    pub fn subfamily(&self) -> &str {
+        self.subfamily.as_str()
+    }
+
+    /// The weight from `1` to `1000`, like `700` for bold
+    pub fn weight(&self) -> u16 {
+        self.weight
+    }
+
+    /// The style
+    pub fn style(&self) -> FontStyle {
+        self.style
+    }
+
+    /// The ranges of the characters that have a glyph, sorted and merged
+    pub fn unicode_ranges(&self) -> &[RangeInclusive<u32>] {
+        self.unicode_ranges.as_slice()
+    }
+
+    /// The ranges in the CSS `unicode-range` notation like `U+20-7E, U+A0-17F`
+    pub fn unicode_range(&self) -> String {
+        self.unicode_ranges
+            .iter()
+            .map(|range| {
+                if range.start() == range.end() {
+                    format!("U+{:X}", range.start())
+                } else {
+                    format!("U+{:X}-{:X}", range.start(), range.end())
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    /// The first name found for the IDs, preferring the English names for Windows
+    fn name(table: &[u8], ids: &[u16]) -> Option<String> {
+        let count = read_u16(table, 2)? as usize;
+        let storage = read_u16(table, 4)? as usize;
+
+        let records = (0..count)
+            .filter_map(|index| {
+                let record = 6 + index * 12;
+
+                Some((
+                    read_u16(table, record)?,
+                    read_u16(table, record + 4)?,
+                    read_u16(table, record + 6)?,
+                    table.get(
+                        storage + read_u16(table, record + 10)? as usize
+                            ..storage
+                                + read_u16(table, record + 10)? as usize
+                                + read_u16(table, record + 8)? as usize,
+                    )?,
+                ))
+            })
+            .collect::<Vec<(u16, u16, u16, &[u8])>>();
+
+        ids.iter().find_map(|id| {
+            records
+                .iter()
+                .filter(|(_, _, name_id, _)| name_id == id)
+                .filter_map(|(platform, language, _, bytes)| {
+                    let rank = match (platform, language) {
+                        (3, 0x0409) => 0,
+                        (3, _) => 1,
+                        (0, _) => 2,
+                        (1, 0) => 3,
+                        _ => return None,
+                    };
+
+                    let name = if *platform == 1 {
+                        // Mac Roman, only the ASCII characters are decoded correctly
+                        bytes.iter().map(|byte| *byte as char).collect::<String>()
+                    } else {
+                        char::decode_utf16(
+                            bytes
+                                .chunks_exact(2)
+                                .map(|pair| u16::from_be_bytes([pair[0], pair[1]])),
+                        )
+                        .collect::<Result<String, _>>()
+                        .ok()?
+                    };
+
+                    let name = name.trim().to_owned();
+
+                    (!name.is_empty()).then_some((rank, name))
+                })
+                .min_by_key(|(rank, _)| *rank)
+                .map(|(_, name)| name)
+        })
+    }
+
+    fn style_from_name(subfamily: &str) -> FontStyle {
+        let subfamily = subfamily.to_ascii_lowercase();
+
+        if subfamily.contains("italic") {
+            FontStyle::Italic
+        } else if subfamily.contains("oblique") {
+            FontStyle::Oblique
+        } else {
+            FontStyle::Normal
+        }
+    }
+
+    /// The characters mapped by the format 12 or format 4 Unicode subtable
+    fn unicode_ranges_from_cmap(cmap: &[u8]) -> Vec<RangeInclusive<u32>> {
+        let count = read_u16(cmap, 2).unwrap_or_default() as usize;
+
+        let subtables = (0..count)
+            .filter_map(|index| {
+                let record = 4 + index * 8;
+                let platform = read_u16(cmap, record)?;
+                let encoding = read_u16(cmap, record + 2)?;
+                let offset = read_u32(cmap, record + 4)? as usize;
+
+                matches!((platform, encoding), (0, _) | (3, 1) | (3, 10))
+                    .then(|| Some((read_u16(cmap, offset)?, offset)))
+                    .flatten()
+            })
+            .collect::<Vec<(u16, usize)>>();
+
+        let mut ranges = if let Some((_, offset)) = subtables.iter().find(|(f, _)| *f == 12) {
+            let groups = read_u32(cmap, offset + 12).unwrap_or_default() as usize;
+
+            (0..groups)
+                .map_while(|group| {
+                    let group = offset + 16 + group * 12;
+
+                    Some(read_u32(cmap, group)?..=read_u32(cmap, group + 4)?)
+                })
+                .collect::<Vec<RangeInclusive<u32>>>()
+        } else if let Some((_, offset)) = subtables.iter().find(|(f, _)| *f == 4) {
+            let segments = read_u16(cmap, offset + 6).unwrap_or_default() as usize / 2;
+
+            (0..segments)
+                .map_while(|segment| {
+                    let end = read_u16(cmap, offset + 14 + segment * 2)?;
+                    let start = read_u16(cmap, offset + 16 + segments * 2 + segment * 2)?;
+
+                    Some(u32::from(start)..=u32::from(end))
+                })
+                // The last segment only maps `U+FFFF` to the missing glyph
+                .filter(|range| *range != (0xFFFF..=0xFFFF))
+                .collect::<Vec<RangeInclusive<u32>>>()
+        } else {
+            Vec::default()
+        };
+
+        ranges.retain(|range| range.start() <= range.end());
+        ranges.sort_by_key(|range| *range.start());
+
+        ranges.into_iter().fold(Vec::new(), |mut merged, range| {
+            match merged.last_mut() {
+                Some(last) if *range.start() <= last.end().saturating_add(1) => {
+                    *last = *last.start()..=*range.end().max(last.end());
+                }
+                _ => merged.push(range),
+            }
+
+            merged
+        })
+    }
+}
+
+/// The tables needed for the [FontMetadata], decompressed if needed
+struct FontTables<'a> {
+    tables: Vec<([u8; 4], Cow<'a, [u8]>)>,
+}
+
+impl<'a> FontTables<'a> {
+    fn parse(bytes: &'a [u8]) -> PuppeteerResult<Self> {
+        match bytes.get(..4) {
+            Some(b"wOF2") => FontTables::woff2(bytes),
+            Some(b"wOFF") => FontTables::woff(bytes),
+            Some(b"\0\x01\0\0" | b"OTTO" | b"true") => FontTables::sfnt(bytes),
+            Some(b"ttcf") => Err(invalid_font("Font collections are not supported")),
+            _ => Err(invalid_font(
+                "The file is not a TTF, OTF, WOFF or WOFF2 font",
+            )),
+        }
+    }
+
+    fn get(&self, tag: &[u8; 4]) -> Option<&Cow<'a, [u8]>> {
+        self.tables
+            .iter()
+            .find(|(table, _)| table == tag)
+            .map(|(_, bytes)| bytes)
+    }
+
+    fn sfnt(bytes: &'a [u8]) -> PuppeteerResult<Self> {
+        let count = read_u16(bytes, 4).ok_or_else(truncated)? as usize;
+
+        let tables = (0..count)
+            .map(|index| {
+                let record = 12 + index * 16;
+                let tag = read_tag(bytes, record).ok_or_else(truncated)?;
+                let offset = read_u32(bytes, record + 8).ok_or_else(truncated)? as usize;
+                let length = read_u32(bytes, record + 12).ok_or_else(truncated)? as usize;
+
+                Ok((tag, offset, length))
+            })
+            .collect::<PuppeteerResult<Vec<([u8; 4], usize, usize)>>>()?
+            .into_iter()
+            .filter(|(tag, _, _)| METADATA_TAGS.contains(&tag))
+            .map(|(tag, offset, length)| {
+                let table = bytes
+                    .get(offset..offset.saturating_add(length))
+                    .ok_or_else(truncated)?;
+
+                Ok((tag, Cow::Borrowed(table)))
+            })
+            .collect::<PuppeteerResult<Vec<([u8; 4], Cow<[u8]>)>>>()?;
+
+        Ok(FontTables { tables })
+    }
+
+    fn woff(bytes: &'a [u8]) -> PuppeteerResult<Self> {
+        let count = read_u16(bytes, 12).ok_or_else(truncated)? as usize;
+
+        let tables = (0..count)
+            .map(|index| {
+                let record = 44 + index * 20;
+                let tag = read_tag(bytes, record).ok_or_else(truncated)?;
+                let offset = read_u32(bytes, record + 4).ok_or_else(truncated)? as usize;
+                let compressed = read_u32(bytes, record + 8).ok_or_else(truncated)? as usize;
+                let length = read_u32(bytes, record + 12).ok_or_else(truncated)? as usize;
+
+                Ok((tag, offset, compressed, length))
+            })
+            .collect::<PuppeteerResult<Vec<([u8; 4], usize, usize, usize)>>>()?
+            .into_iter()
+            .filter(|(tag, _, _, _)| METADATA_TAGS.contains(&tag))
+            .map(|(tag, offset, compressed, length)| {
+                let table = bytes
+                    .get(offset..offset.saturating_add(compressed))
+                    .ok_or_else(truncated)?;
+
+                if compressed >= length {
+                    return Ok((tag, Cow::Borrowed(table)));
+                }
+
+                let table = miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(
+                    table,
+                    length.min(MAX_DECOMPRESSED_FONT_SIZE),
+                )
+                .map_err(|error| invalid_font(&format!("Could not inflate a table: {error}")))?;
+
+                Ok((tag, Cow::Owned(table)))
+            })
+            .collect::<PuppeteerResult<Vec<([u8; 4], Cow<[u8]>)>>>()?;
+
+        Ok(FontTables { tables })
+    }
+
+    fn woff2(bytes: &'a [u8]) -> PuppeteerResult<Self> {
+        if read_tag(bytes, 4) == Some(*b"ttcf") {
+            return Err(invalid_font("Font collections are not supported"));
+        }
+
+        let count = read_u16(bytes, 12).ok_or_else(truncated)? as usize;
+        let compressed_length = read_u32(bytes, 20).ok_or_else(truncated)? as usize;
+
+        // The header is 48 bytes and followed by the table directory
+        let mut offset = 48;
+        let mut entries = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            let flags = *bytes.get(offset).ok_or_else(truncated)?;
+            offset += 1;
+
+            let tag = match flags & 0x3F {
+                0x3F => {
+                    let tag = read_tag(bytes, offset).ok_or_else(truncated)?;
+                    offset += 4;
+
+                    tag
+                }
+                index => *WOFF2_KNOWN_TAGS[index as usize],
+            };
+
+            let length = read_base128(bytes, &mut offset)?;
+
+            // `glyf` and `loca` are transformed by default, the other tables only
+            // when the transform version is not zero
+            let transform = flags >> 6;
+            let transformed = if &tag == b"glyf" || &tag == b"loca" {
+                transform == 0
+            } else {
+                transform != 0
+            };
+
+            let stored = if transformed {
+                read_base128(bytes, &mut offset)?
+            } else {
+                length
+            };
+
+            entries.push((tag, stored));
+        }
+
+        let total = entries
+            .iter()
+            .try_fold(0usize, |total, (_, stored)| total.checked_add(*stored))
+            .filter(|total| *total <= MAX_DECOMPRESSED_FONT_SIZE)
+            .ok_or_else(|| invalid_font("The decompressed tables are too large"))?;
+
+        let compressed = bytes
+            .get(offset..offset.saturating_add(compressed_length))
+            .ok_or_else(truncated)?;
+
+        let mut decompressed = Vec::with_capacity(total);
+        brotli_decompressor::Decompressor::new(compressed, 4096)
+            .take(total as u64)
+            .read_to_end(&mut decompressed)
+            .map_err(|error| invalid_font(&format!("Could not decompress the tables: {error}")))?;
+
+        let mut start = 0;
+        let mut tables = Vec::new();
+
+        for (tag, stored) in entries {
+            let end = start + stored;
+
+            if METADATA_TAGS.contains(&&tag) {
+                let table = decompressed.get(start..end).ok_or_else(truncated)?;
+                tables.push((tag, Cow::Owned(table.to_vec())));
+            }
+
+            start = end;
+        }
+
+        Ok(FontTables { tables })
+    }
+}
+
+fn invalid_font(reason: &str) -> PuppeteerError {
+    PuppeteerError::InvalidFontData(reason.to_owned())
+}
+
+fn truncated() -> PuppeteerError {
+    invalid_font("The font is truncated")
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(
+        bytes.get(offset..offset.checked_add(2)?)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        bytes.get(offset..offset.checked_add(4)?)?.try_into().ok()?,
+    ))
+}
+
+fn read_tag(bytes: &[u8], offset: usize) -> Option<[u8; 4]> {
+    bytes.get(offset..offset.checked_add(4)?)?.try_into().ok()
+}
+
+/// A `UIntBase128` of the WOFF2 table directory
+fn read_base128(bytes: &[u8], offset: &mut usize) -> PuppeteerResult<usize> {
+    let mut value = 0u32;
+
+    for index in 0..5 {
+        let byte = *bytes.get(*offset).ok_or_else(truncated)?;
+        *offset += 1;
+
+        if (index == 0 && byte == 0x80) || value & 0xFE00_0000 != 0 {
+            return Err(invalid_font("Invalid table directory"));
+        }
+
+        value = (value << 7) | u32::from(byte & 0x7F);
+
+        if byte & 0x80 == 0 {
+            return Ok(value as usize);
+        }
+    }
+
+    Err(invalid_font("Invalid table directory"))
+}
+
+#[cfg(test)]
+mod font_metadata_checks {
+    use super::*;
+
+    /// The `OS/2`, `cmap` and `name` tables of a minimal TrueType font
+    fn tables() -> [([u8; 4], Vec<u8>); 3] {
+        let mut os2 = vec![0u8; 78];
+        os2[4..6].copy_from_slice(&700u16.to_be_bytes());
+        os2[62..64].copy_from_slice(&1u16.to_be_bytes());
+
+        let mut cmap = [0u16, 1, 3, 1, 0, 12]
+            .iter()
+            .flat_map(|value| value.to_be_bytes())
+            .collect::<Vec<u8>>();
+        // Format 4 with the segments `U+20-7E`, `U+A0-FF` and the final `U+FFFF`
+        cmap.extend(
+            [
+                4u16, 40, 0, 6, 4, 1, 2, 0x7E, 0xFF, 0xFFFF, 0, 0x20, 0xA0, 0xFFFF, 0, 0, 1, 0, 0,
+                0,
+            ]
+            .iter()
+            .flat_map(|value| value.to_be_bytes()),
+        );
+
+        let family = "Note Sans"
+            .encode_utf16()
+            .flat_map(u16::to_be_bytes)
+            .collect::<Vec<u8>>();
+        let mut name = [0u16, 2, 30]
+            .iter()
+            .chain(&[1, 0, 0, 1, 3, 0][..])
+            .chain(&[3, 1, 0x0409, 1, family.len() as u16, 3][..])
+            .flat_map(|value| value.to_be_bytes())
+            .collect::<Vec<u8>>();
+        name.extend(b"Mac");
+        name.extend(&family);
+
+        [(*b"OS/2", os2), (*b"cmap", cmap), (*b"name", name)]
+    }
+
+    fn sfnt() -> Vec<u8> {
+        let tables = tables();
+        let mut font = [0x0001u16, 0, 3, 0, 0, 0]
+            .iter()
+            .flat_map(|value| value.to_be_bytes())
+            .collect::<Vec<u8>>();
+        let mut offset = 12 + tables.len() * 16;
+
+        for (tag, table) in tables.iter() {
+            font.extend(tag);
+            font.extend(0u32.to_be_bytes());
+            font.extend((offset as u32).to_be_bytes());
+            font.extend((table.len() as u32).to_be_bytes());
+            offset += table.len();
+        }
+
+        tables.iter().for_each(|(_, table)| font.extend(table));
+
+        font
+    }
+
+    /// The same font as [sfnt] with the tables compressed using zlib
+    fn woff() -> Vec<u8> {
+        let tables = tables();
+        let mut font = b"wOFF\0\x01\0\0\0\0\0\0\0\x03".to_vec();
+        font.resize(44, 0);
+        let mut offset = 44 + tables.len() * 20;
+        let mut data = Vec::new();
+
+        for (tag, table) in tables.iter() {
+            let mut compressed = miniz_oxide::deflate::compress_to_vec_zlib(table, 6);
+
+            // Tables are stored uncompressed when compressing does not make them smaller
+            if compressed.len() >= table.len() {
+                compressed.clone_from(table);
+            }
+
+            font.extend(tag);
+            font.extend((offset as u32).to_be_bytes());
+            font.extend((compressed.len() as u32).to_be_bytes());
+            font.extend((table.len() as u32).to_be_bytes());
+            font.extend(0u32.to_be_bytes());
+            offset += compressed.len();
+            data.extend(compressed);
+        }
+
+        font.extend(data);
+
+        font
+    }
+
+    #[test]
+    fn parses_font_tables() {
+        let metadata = FontMetadata::parse(&sfnt()).unwrap();
+        assert_eq!(metadata, FontMetadata::parse(&woff()).unwrap());
+        assert_eq!("Note Sans", metadata.family());
+        assert_eq!("Regular", metadata.subfamily());
+        assert_eq!(700, metadata.weight());
+        assert_eq!(FontStyle::Italic, metadata.style());
+        assert_eq!("U+20-7E, U+A0-FF", metadata.unicode_range());
+
+        let warteg =
+            FontMetadata::parse(include_bytes!("../examples/assets/fonts/warteg.woff2")).unwrap();
+        assert_eq!("Warteg", warteg.family());
+        assert!(warteg
+            .unicode_ranges()
+            .iter()
+            .any(|range| range.contains(&0x41)));
+
+        let mut truncated = sfnt();
+        truncated.truncate(60);
+        assert!(FontMetadata::parse(&truncated).is_err());
+        assert!(FontMetadata::parse(b"\x89PNG\r\n\x1a\n").is_err());
+    }
+}
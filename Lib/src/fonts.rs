@@ -0,0 +1,470 @@
+use crate::{
+    utils::js_string, AssetFile, FontMetadata, PuppeteerError, PuppeteerResult, StaticAsset,
+};
+use base64ct::{Base64, Encoding};
+use file_format::FileFormat;
+use std::borrow::Cow;
+
+/// The `font-style` of a [FontVariant]
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum FontStyle {
+    /// Upright glyphs
+    #[default]
+    Normal,
+    /// Italic glyphs
+    Italic,
+    /// Slanted glyphs
+    Oblique,
+}
+
+impl FontStyle {
+    /// The CSS value
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Italic => "italic",
+            Self::Oblique => "oblique",
+        }
+    }
+}
+
+/// The `font-display` of a [FontFamily], how text is shown while the font loads
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum FontDisplay {
+    /// Let the webview decide
+    Auto,
+    /// Hide the text for a short time, then swap when the font is loaded
+    Block,
+    /// Show a fallback font immediately and swap when the font is loaded
+    #[default]
+    Swap,
+    /// Hide the text for a very short time and swap only if the font loads quickly
+    Fallback,
+    /// Only use the font if it is already available
+    Optional,
+}
+
+impl FontDisplay {
+    /// The CSS value
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Block => "block",
+            Self::Swap => "swap",
+            Self::Fallback => "fallback",
+            Self::Optional => "optional",
+        }
+    }
+}
+
+/// One font file of a [FontFamily] with the weights, style and characters it covers
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct FontVariant {
+    name: Cow<'static, str>,
+    bytes: Cow<'static, [u8]>,
+    weight: (u16, u16),
+    style: FontStyle,
+    unicode_range: Option<String>,
+    preload: bool,
+}
+
+impl FontVariant {
+    /// A variant with the weight `400` and the [FontStyle::Normal] style.
+    /// The asset must be a WOFF2, WOFF, TTF or OTF file
+    pub fn new(asset: StaticAsset) -> Self {
+        FontVariant {
+            name: Cow::Borrowed(asset.name),
+            bytes: Cow::Borrowed(asset.bytes),
+            weight: (400, 400),
+            style: FontStyle::default(),
+            unicode_range: Option::default(),
+            preload: false,
+        }
+    }
+
+    /// A variant from a font loaded at runtime using [crate::AssetFileLoader]
+    pub fn from_asset_file(asset: &AssetFile) -> Self {
+        FontVariant {
+            name: Cow::Owned(asset.name.to_string()),
+            bytes: Cow::Owned(asset.bytes.to_vec()),
+            ..FontVariant::new(StaticAsset {
+                name: "",
+                bytes: &[],
+            })
+        }
+    }
+
+    /// Read the family, weight, style and characters from the tables of the font
+    pub fn metadata(&self) -> PuppeteerResult<FontMetadata> {
+        FontMetadata::parse(&self.bytes)
+    }
+
+    /// Use the weight, style and characters of the [FontMetadata]
+    pub fn with_metadata(mut self, metadata: &FontMetadata) -> Self {
+        self.weight = (metadata.weight(), metadata.weight());
+        self.style = metadata.style();
+        self.unicode_range =
+            (!metadata.unicode_ranges().is_empty()).then(|| metadata.unicode_range());
+
+        self
+    }
+
+    /// The `font-weight` from `1` to `1000`, like `700` for bold
+    pub fn weight(mut self, weight: u16) -> Self {
+        self.weight = (weight, weight);
+
+        self
+    }
+
+    /// The range of weights of a variable font, like `100` to `900`
+    pub fn weight_range(mut self, min: u16, max: u16) -> Self {
+        self.weight = (min, max);
+
+        self
+    }
+
+    /// The `font-style`
+    pub fn style(mut self, style: FontStyle) -> Self {
+        self.style = style;
+
+        self
+    }
+
+    /// The characters of the font, like `U+0000-00FF, U+0131`, so that the webview
+    /// only loads the font when these characters are used
+    pub fn unicode_range(mut self, range: &str) -> Self {
+        self.unicode_range.replace(range.to_owned());
+
+        self
+    }
+
+    /// Load the font with the document instead of when the text using it is painted,
+    /// see [FontFamily::preload_script]
+    pub fn preload(mut self) -> Self {
+        self.preload = true;
+
+        self
+    }
+
+    /// Is the variant preloaded
+    pub fn is_preloaded(&self) -> bool {
+        self.preload
+    }
+
+    /// The value of the CSS `format()` of the asset
+    pub fn format(&self) -> PuppeteerResult<&'static str> {
+        match FileFormat::from_bytes(&self.bytes) {
+            FileFormat::WebOpenFontFormat2 => Ok("woff2"),
+            FileFormat::WebOpenFontFormat => Ok("woff"),
+            FileFormat::Truetype => Ok("truetype"),
+            FileFormat::Opentype => Ok("opentype"),
+            format => Err(PuppeteerError::UnsupportedFontFormat {
+                name: self.name.to_string(),
+                format: format.name().to_owned(),
+            }),
+        }
+    }
+
+    fn validate(&self) -> PuppeteerResult<()> {
+        let (min, max) = self.weight;
+
+        if min == 0 || max > 1000 || min > max {
+            return Err(PuppeteerError::InvalidFontVariant(format!(
+                "The weight `{min} {max}` of `{}` is not between 1 and 1000",
+                self.name
+            )));
+        }
+
+        if let Some(range) = self.unicode_range.as_ref() {
+            if !range.split(',').all(FontVariant::is_unicode_range) {
+                return Err(PuppeteerError::InvalidFontVariant(format!(
+                    "The unicode range `{range}` of `{}` is invalid",
+                    self.name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The font encoded as a `data:` URL
+    fn data_url(&self) -> String {
+        String::from("data:")
+            + FileFormat::from_bytes(&self.bytes).media_type()
+            + ";base64,"
+            + &Base64::encode_string(&self.bytes)
+    }
+
+    /// A range like `U+0025-00FF`, `U+4??` or `U+0131`
+    fn is_unicode_range(range: &str) -> bool {
+        let Some(range) = range.trim().strip_prefix("U+") else {
+            return false;
+        };
+
+        let is_hex = |value: &str, wildcards: bool| {
+            (1..=6).contains(&value.len())
+                && value.chars().all(|character| {
+                    character.is_ascii_hexdigit() || (wildcards && character == '?')
+                })
+        };
+
+        match range.split_once('-') {
+            Some((start, end)) => is_hex(start, false) && is_hex(end, false),
+            None => is_hex(range, true),
+        }
+    }
+}
+
+/// A font family made of [FontVariant]s, added to the [crate::Shell] as `@font-face` rules
+///
+/// #### Example
+/// ```rust
+/// use puppeteer::{FontDisplay, FontFamily, FontStyle, FontVariant, Shell, StaticAsset};
+///
+/// // Use `puppeteer::asset!()` to include the files
+/// const REGULAR: StaticAsset = StaticAsset { name: "inter", bytes: b"wOF2\0\x01\0\0" };
+/// const ITALIC: StaticAsset = StaticAsset { name: "inter-italic", bytes: b"wOF2\0\x01\0\0" };
+///
+/// let family = FontFamily::new("Inter")
+///     .display(FontDisplay::Fallback)
+///     .variant(FontVariant::new(REGULAR).weight_range(100, 900).preload())
+///     .variant(FontVariant::new(ITALIC).style(FontStyle::Italic));
+///
+/// let shell = Shell::new().add_font_family(&family).unwrap();
+/// ```
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct FontFamily {
+    name: String,
+    display: FontDisplay,
+    variants: Vec<FontVariant>,
+}
+
+impl FontFamily {
+    /// A family without any variant using [FontDisplay::Swap]
+    pub fn new(name: &str) -> Self {
+        FontFamily {
+            name: name.to_owned(),
+            display: FontDisplay::default(),
+            variants: Vec::default(),
+        }
+    }
+
+    /// Change the `font-display` of every variant
+    pub fn display(mut self, display: FontDisplay) -> Self {
+        self.display = display;
+
+        self
+    }
+
+    /// Add a variant
+    pub fn variant(mut self, variant: FontVariant) -> Self {
+        self.variants.push(variant);
+
+        self
+    }
+
+    /// Group the fonts into families using the family, weight, style and characters
+    /// read from their tables, see [FontMetadata]. The families are in the order
+    /// of their first font
+    pub fn detect(variants: impl IntoIterator<Item = FontVariant>) -> PuppeteerResult<Vec<Self>> {
+        variants
+            .into_iter()
+            .try_fold(Vec::<FontFamily>::new(), |mut families, variant| {
+                let metadata = variant.metadata()?;
+                let variant = variant.with_metadata(&metadata);
+
+                match families
+                    .iter_mut()
+                    .find(|family| family.name == metadata.family())
+                {
+                    Some(family) => family.variants.push(variant),
+                    None => families.push(FontFamily::new(metadata.family()).variant(variant)),
+                }
+
+                Ok(families)
+            })
+    }
+
+    /// The name of the family used in the CSS `font-family`
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// The variants of the family
+    pub fn variants(&self) -> &[FontVariant] {
+        self.variants.as_slice()
+    }
+
+    /// The `@font-face` rule of each variant.
+    /// Returns an error if a font is not a WOFF2, WOFF, TTF or OTF file or a variant is invalid
+    pub fn css(&self) -> PuppeteerResult<String> {
+        self.variants
+            .iter()
+            .try_fold(String::new(), |css, variant| {
+                variant.validate()?;
+
+                let (min, max) = variant.weight;
+                let weight = if min == max {
+                    min.to_string()
+                } else {
+                    min.to_string() + " " + &max.to_string()
+                };
+
+                let unicode_range = variant
+                    .unicode_range
+                    .as_ref()
+                    .map(|range| String::from(" unicode-range: ") + range + ";")
+                    .unwrap_or_default();
+
+                Ok(css
+                    + "@font-face {font-family: "
+                    + &css_string(&self.name)
+                    + "; src: url("
+                    + &css_string(&variant.data_url())
+                    + ") format(\""
+                    + variant.format()?
+                    + "\"); font-weight: "
+                    + &weight
+                    + "; font-style: "
+                    + variant.style.as_str()
+                    + "; font-display: "
+                    + self.display.as_str()
+                    + ";"
+                    + &unicode_range
+                    + "}")
+            })
+    }
+
+    /// The script loading the preloaded variants using `document.fonts.load()`.
+    /// The fonts are embedded as `data:` URLs, so a `<link rel="preload">` would add
+    /// each of them to the document a second time
+    pub fn preload_script(&self) -> PuppeteerResult<String> {
+        let loads = self
+            .variants
+            .iter()
+            .filter(|variant| variant.is_preloaded())
+            .try_fold(String::new(), |loads, variant| {
+                variant.format()?;
+
+                let font = String::from(variant.style.as_str())
+                    + " "
+                    + &variant.weight.0.to_string()
+                    + " 1em "
+                    + &css_string(&self.name);
+
+                Ok::<_, PuppeteerError>(loads + "document.fonts.load(" + &js_string(&font) + ");")
+            })?;
+
+        if loads.is_empty() {
+            return Ok(loads);
+        }
+
+        Ok(String::from("<script>") + &loads + "</script>")
+    }
+}
+
+impl core::fmt::Debug for FontVariant {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("FontVariant")
+            .field("name", &self.name)
+            .field("bytes", &blake3::hash(&self.bytes))
+            .field("weight", &self.weight)
+            .field("style", &self.style)
+            .field("unicode_range", &self.unicode_range)
+            .field("preload", &self.preload)
+            .finish()
+    }
+}
+
+/// A CSS string that cannot end the string or the `<style>` element
+fn css_string(value: &str) -> String {
+    let mut outcome = String::with_capacity(value.len() + 2);
+    outcome.push('"');
+
+    value.chars().for_each(|character| match character {
+        '"' | '\\' => {
+            outcome.push('\\');
+            outcome.push(character);
+        }
+        '<' => outcome.push_str("\\3c "),
+        '\n' => outcome.push_str("\\a "),
+        _ => outcome.push(character),
+    });
+
+    outcome.push('"');
+
+    outcome
+}
+
+#[cfg(test)]
+mod fonts_checks {
+    use super::*;
+    use crate::{Shell, UiPaint};
+
+    const WOFF2: StaticAsset = StaticAsset {
+        name: "sans",
+        bytes: b"wOF2\0\x01\0\0",
+    };
+    const TTF: StaticAsset = StaticAsset {
+        name: "sans-italic",
+        bytes: b"\0\x01\0\0\0\x0a",
+    };
+    const PNG: StaticAsset = StaticAsset {
+        name: "logo",
+        bytes: b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR",
+    };
+
+    #[test]
+    fn declares_font_faces() {
+        let family = FontFamily::new("Sans \"Pro\"</style>")
+            .display(FontDisplay::Optional)
+            .variant(FontVariant::new(WOFF2).weight_range(100, 900).preload())
+            .variant(
+                FontVariant::new(TTF)
+                    .weight(700)
+                    .style(FontStyle::Italic)
+                    .unicode_range("U+0000-00FF, U+4??"),
+            );
+
+        let css = family.css().unwrap();
+        assert_eq!(2, css.matches("@font-face {").count());
+        assert!(css.starts_with(r#"@font-face {font-family: "Sans \"Pro\"\3c /style>"; src: url("data:font/woff2;base64,"#));
+        assert!(css.contains(
+            r#"format("woff2"); font-weight: 100 900; font-style: normal; font-display: optional;}"#
+        ));
+        assert!(css.contains(r#"format("truetype"); font-weight: 700; font-style: italic; font-display: optional; unicode-range: U+0000-00FF, U+4??;}"#));
+
+        let shell = Shell::new().add_font_family(&family).unwrap();
+        let html = shell.to_html();
+        assert!(!html.contains(r#"<link rel="preload""#));
+        assert_eq!(1, html.matches("data:font/woff2;base64,").count());
+        assert!(html.contains(
+            r#"</style><script>document.fonts.load("normal 100 1em \"Sans \\\"Pro\\\"\\3c /style\u003e\"");</script></head>"#
+        ));
+        assert!(!html.contains("FontFace"));
+
+        assert!(matches!(
+            FontFamily::new("Logo").variant(FontVariant::new(PNG)).css(),
+            Err(PuppeteerError::UnsupportedFontFormat { .. })
+        ));
+        assert!(FontFamily::new("Sans")
+            .variant(FontVariant::new(WOFF2).weight(0))
+            .css()
+            .is_err());
+        assert!(FontFamily::new("Sans")
+            .variant(FontVariant::new(WOFF2).unicode_range("0000-00FF"))
+            .css()
+            .is_err());
+
+        let warteg = StaticAsset {
+            name: "warteg",
+            bytes: include_bytes!("../examples/assets/fonts/warteg.woff2"),
+        };
+        let families =
+            FontFamily::detect([FontVariant::new(warteg), FontVariant::new(warteg)]).unwrap();
+        assert_eq!(1, families.len());
+        assert_eq!("Warteg", families[0].name());
+        assert_eq!(2, families[0].variants().len());
+        assert!(FontFamily::detect([FontVariant::new(WOFF2)]).is_err());
+    }
+}
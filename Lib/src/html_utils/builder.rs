@@ -0,0 +1,136 @@
+use crate::VNode;
+
+/// Creates typed HTML elements as [VNode]s.
+/// Text added using [Html::text] is escaped automatically and the nodes implement
+/// [crate::UiPaint] so they can be used with [crate::ModifyView], [crate::Shell] and [crate::VDom].
+///
+/// #### Example
+/// ```rust
+/// use puppeteer::{Html, UiPaint};
+///
+/// let view = Html::div()
+///     .class("counter")
+///     .child(Html::span().id("count").child(Html::text("<0>")))
+///     .child(Html::button().on("click", "increment").child(Html::text("+")));
+///
+/// assert_eq!(
+///     view.to_html(),
+///     r#"<div class="counter"><span id="count">&lt;0&gt;</span><button data-pp-on-click="increment">+</button></div>"#
+/// );
+/// ```
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct Html;
+
+macro_rules! elements {
+    ($($tag:ident),* $(,)?) => {
+        impl Html {
+            $(
+                #[doc = concat!("Create a `<", stringify!($tag), ">` element")]
+                pub fn $tag() -> VNode {
+                    VNode::element(stringify!($tag))
+                }
+            )*
+        }
+    };
+}
+
+elements!(
+    a, article, aside, br, button, code, div, em, footer, form, h1, h2, h3, h4, h5, h6, header, hr,
+    img, input, label, li, link, main, meta, nav, ol, option, p, pre, section, select, small, span,
+    strong, table, tbody, td, textarea, th, thead, tr, ul,
+);
+
+impl Html {
+    /// Create an element that has no constructor in [Html] like a custom element
+    pub fn element(tag: &str) -> VNode {
+        VNode::element(tag)
+    }
+
+    /// Create a text node which is escaped when rendered
+    pub fn text(text: &str) -> VNode {
+        VNode::text(text)
+    }
+
+    /// Create a node from HTML that is rendered without escaping.
+    /// Never use this with content that is not trusted
+    pub fn raw(html: &str) -> VNode {
+        VNode::raw(html)
+    }
+}
+
+impl VNode {
+    /// Set the `id` attribute
+    pub fn id(self, id: &str) -> Self {
+        self.attr("id", id)
+    }
+
+    /// Set the `class` attribute
+    pub fn class(self, class: &str) -> Self {
+        self.attr("class", class)
+    }
+
+    /// Send the `message` to `Puppeteer::parse()` when the DOM `event` like `click` occurs.
+    /// The event is delegated by the [crate::PUPPETEER_RUNTIME_SCRIPT] instead of using
+    /// an inline handler so that it works with a [crate::ContentSecurityPolicy].
+    /// The element gets a `data-pp-on-{event}` attribute holding the `message`.
+    /// Messages starting with `puppeteer:` are dropped except the messages of routes,
+    /// components, bindings and modals
+    pub fn on(self, event: &str, message: &str) -> Self {
+        self.attr(&(String::from("data-pp-on-") + event), message)
+    }
+
+    /// Send the `prefix` followed by the current value of the element to `Puppeteer::parse()`
+    /// when the DOM `event` like `input` occurs.
+    /// The element gets a `data-pp-value-on-{event}` attribute holding the `prefix`,
+    /// see [VNode::on]
+    pub fn on_value(self, event: &str, prefix: &str) -> Self {
+        self.attr(&(String::from("data-pp-value-on-") + event), prefix)
+    }
+}
+
+/// Escapes text so that it is parsed as a text node and never as HTML markup.
+/// Quotes are escaped too so that the outcome is also safe inside attribute values
+pub fn escape_html(value: &str) -> String {
+    let mut outcome = String::with_capacity(value.len());
+
+    value.chars().for_each(|character| match character {
+        '&' => outcome.push_str("&amp;"),
+        '<' => outcome.push_str("&lt;"),
+        '>' => outcome.push_str("&gt;"),
+        '"' => outcome.push_str("&quot;"),
+        '\'' => outcome.push_str("&#39;"),
+        _ => outcome.push(character),
+    });
+
+    outcome
+}
+
+#[cfg(test)]
+mod builder_checks {
+    use super::*;
+    use crate::UiPaint;
+
+    #[test]
+    fn builds_escaped_markup() {
+        let form = Html::form()
+            .attr("bad name", "ignored")
+            .attr("data-x", "\"><script>")
+            .child(
+                Html::input()
+                    .id("search")
+                    .on_value("input", "search>")
+                    .child(Html::text("ignored")),
+            )
+            .child(Html::p().child(Html::text("<b>'&'</b>")))
+            .child(Html::raw("<hr>"));
+
+        assert_eq!(
+            concat!(
+                r#"<form data-x="&quot;&gt;&lt;script&gt;">"#,
+                r#"<input data-pp-value-on-input="search&gt;" id="search">"#,
+                r#"<p>&lt;b&gt;&#39;&amp;&#39;&lt;/b&gt;</p><hr></form>"#
+            ),
+            form.to_html()
+        );
+    }
+}
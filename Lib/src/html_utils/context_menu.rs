@@ -1,4 +1,4 @@
-use crate::StaticCowStr;
+use crate::{utils::js_string, StaticCowStr};
 
 /// This provides the context menu builder
 #[derive(Debug, PartialEq, Eq, Default)]
@@ -50,20 +50,22 @@ impl ContextMenu {
 
     /// Builds the context script by using the `self.id` as event listener target
     pub fn build_script(&self) -> StaticCowStr {
+        let id = js_string(self.id);
+
         StaticCowStr::Borrowed(
             "  
         <script>
             function hideCustomContextMenu() {
-                document.getElementById('",
-        ) + self.id
-            + "').style.display = \"none\";
+                document.getElementById(",
+        ) + StaticCowStr::Owned(id.clone())
+            + ").style.display = \"none\";
             }
             // toggling the menu on right click to the page
             function showCustomContextMenu(event) {
                 event.preventDefault();
-                var myContextMenu = document.getElementById('"
-            + self.id
-            + "');
+                var myContextMenu = document.getElementById("
+            + StaticCowStr::Owned(id)
+            + ");
                 if (myContextMenu.style.display == \"block\") {
                 myContextMenu.style.display = \"none\";
                 }
@@ -3,3 +3,12 @@ pub use title_bar::*;
 
 mod context_menu;
 pub use context_menu::*;
+
+mod builder;
+pub use builder::*;
+
+mod toast;
+pub use toast::*;
+
+mod script;
+pub use script::*;
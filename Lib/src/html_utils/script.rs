@@ -0,0 +1,135 @@
+use crate::{escape_html, UiPaint};
+use std::borrow::Cow;
+
+/// Where a [Script] is added in the document of the [crate::Shell]
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum ScriptPlacement {
+    /// In the `<head>`, after the [crate::PUPPETEER_RUNTIME_SCRIPT]
+    Head,
+    /// At the end of the `<body>`, after the app element
+    #[default]
+    BodyEnd,
+}
+
+/// How the webview loads and runs a [Script]
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum ScriptLoading {
+    /// Run when it is parsed, blocking the parsing of the document
+    #[default]
+    Classic,
+    /// Run after the document is parsed. Only applies to scripts with a source
+    Defer,
+    /// Run as soon as it is loaded. Only applies to scripts with a source
+    Async,
+    /// Run as a JavaScript module, which is always deferred
+    Module,
+}
+
+/// A `<script>` element added to the [crate::Shell] using [crate::Shell::add_script_element].
+/// The scripts of each [ScriptPlacement] are added in the order they were added to the [crate::Shell].
+///
+/// #### Example
+/// ```rust
+/// use puppeteer::{Script, Shell};
+///
+/// let shell = Shell::new()
+///     .add_script_element(Script::source("/vendor/chart.js").defer().in_head())
+///     .add_script_element(Script::inline("import { draw } from '/draw.js'; draw();").module());
+/// ```
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct Script {
+    source: Option<String>,
+    content: String,
+    placement: ScriptPlacement,
+    loading: ScriptLoading,
+}
+
+impl Script {
+    /// A script with the JavaScript `content`
+    pub fn inline(content: &str) -> Self {
+        Script {
+            source: Option::default(),
+            content: content.to_owned(),
+            placement: ScriptPlacement::default(),
+            loading: ScriptLoading::default(),
+        }
+    }
+
+    /// A script loaded from the `url`
+    pub fn source(url: &str) -> Self {
+        Script {
+            source: Some(url.to_owned()),
+            ..Script::inline("")
+        }
+    }
+
+    /// Add the script to the `<head>` instead of the end of the `<body>`
+    pub fn in_head(mut self) -> Self {
+        self.placement = ScriptPlacement::Head;
+
+        self
+    }
+
+    /// Run the script after the document is parsed
+    pub fn defer(mut self) -> Self {
+        self.loading = ScriptLoading::Defer;
+
+        self
+    }
+
+    /// Run the script as soon as it is loaded
+    pub fn asynchronous(mut self) -> Self {
+        self.loading = ScriptLoading::Async;
+
+        self
+    }
+
+    /// Run the script as a JavaScript module
+    pub fn module(mut self) -> Self {
+        self.loading = ScriptLoading::Module;
+
+        self
+    }
+
+    /// The URL the script is loaded from
+    pub fn url(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// The JavaScript content of an inline script
+    pub fn content(&self) -> &str {
+        self.content.as_str()
+    }
+
+    /// Where the script is added
+    pub fn placement(&self) -> ScriptPlacement {
+        self.placement
+    }
+
+    /// How the script is loaded
+    pub fn loading(&self) -> ScriptLoading {
+        self.loading
+    }
+}
+
+impl UiPaint for Script {
+    fn to_html(&self) -> Cow<str> {
+        let attributes = match self.loading {
+            ScriptLoading::Classic => "",
+            ScriptLoading::Defer => " defer",
+            ScriptLoading::Async => " async",
+            ScriptLoading::Module => r#" type="module""#,
+        };
+
+        let source = self
+            .source
+            .as_ref()
+            .map(|url| String::from(r#" src=""#) + &escape_html(url) + "\"")
+            .unwrap_or_default();
+
+        // `</script` ends the element even inside a JavaScript string
+        let content = self.content.replace("</script", r"<\/script");
+
+        Cow::Owned(String::from("<script") + attributes + &source + ">" + &content + "</script>")
+    }
+}
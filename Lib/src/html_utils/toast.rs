@@ -0,0 +1,306 @@
+use crate::{Html, StaticCowStr, StaticStr, UiPaint, VNode};
+use std::borrow::Cow;
+
+/// The element the toasts are stacked in, added to every [crate::Shell]
+pub const TOAST_CONTAINER_ELEMENT: &str =
+    r#"<div id="pp-toasts" class="pp-toasts" aria-live="polite"></div>"#;
+
+/// The number of milliseconds a toast is shown by default
+pub const DEFAULT_TOAST_TIMEOUT_MS: u32 = 5000;
+
+/// How important a [Toast] is
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum ToastSeverity {
+    /// A neutral message
+    #[default]
+    Info,
+    /// An operation succeeded
+    Success,
+    /// Something needs the attention of the user
+    Warning,
+    /// An operation failed
+    Error,
+}
+
+impl ToastSeverity {
+    /// The name used in the `pp-toast-{severity}` class
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Success => "success",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// A transient message stacked in the [TOAST_CONTAINER_ELEMENT].
+/// Show it using [crate::ModifyView::Toast], for example from `Puppeteer::error_handler()`
+/// instead of replacing the whole app.
+///
+/// #### Example
+/// ```rust
+/// use puppeteer::{ModifyView, Toast};
+///
+/// let view = ModifyView::Toast(
+///     Toast::success("File saved")
+///         .timeout(3000)
+///         .action("Undo", "undo_save"),
+/// );
+/// ```
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct Toast {
+    message: String,
+    severity: ToastSeverity,
+    timeout_ms: Option<u32>,
+    action: Option<(String, String)>,
+}
+
+impl Toast {
+    /// Create an [ToastSeverity::Info] toast shown for [DEFAULT_TOAST_TIMEOUT_MS]
+    pub fn new(message: &str) -> Self {
+        Toast {
+            message: message.to_owned(),
+            severity: ToastSeverity::default(),
+            timeout_ms: Some(DEFAULT_TOAST_TIMEOUT_MS),
+            action: Option::default(),
+        }
+    }
+
+    /// Create a [ToastSeverity::Success] toast
+    pub fn success(message: &str) -> Self {
+        Toast::new(message).severity(ToastSeverity::Success)
+    }
+
+    /// Create a [ToastSeverity::Warning] toast
+    pub fn warning(message: &str) -> Self {
+        Toast::new(message).severity(ToastSeverity::Warning)
+    }
+
+    /// Create a [ToastSeverity::Error] toast
+    pub fn error(message: &str) -> Self {
+        Toast::new(message).severity(ToastSeverity::Error)
+    }
+
+    /// Change the severity
+    pub fn severity(mut self, severity: ToastSeverity) -> Self {
+        self.severity = severity;
+
+        self
+    }
+
+    /// Remove the toast after the number of milliseconds
+    pub fn timeout(mut self, milliseconds: u32) -> Self {
+        self.timeout_ms.replace(milliseconds);
+
+        self
+    }
+
+    /// Keep the toast until the user closes it
+    pub fn persistent(mut self) -> Self {
+        self.timeout_ms.take();
+
+        self
+    }
+
+    /// Add a button that sends the IPC `message` to `Puppeteer::parse()` and closes the toast
+    pub fn action(mut self, label: &str, message: &str) -> Self {
+        self.action.replace((label.to_owned(), message.to_owned()));
+
+        self
+    }
+
+    /// The number of milliseconds the toast is shown or `None` if it is persistent
+    pub fn timeout_ms(&self) -> Option<u32> {
+        self.timeout_ms
+    }
+
+    fn node(&self) -> VNode {
+        let role = match self.severity {
+            ToastSeverity::Warning | ToastSeverity::Error => "alert",
+            _ => "status",
+        };
+
+        let mut toast = Html::div()
+            .class(&(String::from("pp-toast pp-toast-") + self.severity.as_str()))
+            .attr("role", role)
+            .child(
+                Html::span()
+                    .class("pp-toast-message")
+                    .child(Html::text(&self.message)),
+            );
+
+        if let Some((label, message)) = self.action.as_ref() {
+            toast = toast.child(
+                Html::button()
+                    .attr("type", "button")
+                    .class("pp-toast-action")
+                    .on("click", message)
+                    .child(Html::text(label)),
+            );
+        }
+
+        toast.child(
+            Html::button()
+                .attr("type", "button")
+                .class("pp-toast-close")
+                .attr("aria-label", "Close")
+                .child(Html::text("×")),
+        )
+    }
+}
+
+impl UiPaint for Toast {
+    fn to_html(&self) -> Cow<str> {
+        Cow::Owned(self.node().to_html().to_string())
+    }
+}
+
+/// Create CSS for the toasts based on the CSS custom properties of the [crate::ColorPalette]
+#[derive(Debug, PartialEq, Eq, Clone, PartialOrd, Ord)]
+pub struct ToastCSS {
+    /// The background color of [ToastSeverity::Info] toasts
+    pub info_color: StaticStr,
+    /// The background color of [ToastSeverity::Success] toasts
+    pub success_color: StaticStr,
+    /// The background color of [ToastSeverity::Warning] toasts
+    pub warning_color: StaticStr,
+    /// The background color of [ToastSeverity::Error] toasts
+    pub error_color: StaticStr,
+    /// The color of the text of [ToastSeverity::Info] toasts
+    pub text_color: StaticStr,
+    /// The color of the text of [ToastSeverity::Success] toasts
+    pub success_text_color: StaticStr,
+    /// The color of the text of [ToastSeverity::Warning] toasts
+    pub warning_text_color: StaticStr,
+    /// The color of the text of [ToastSeverity::Error] toasts
+    pub error_text_color: StaticStr,
+    /// The distance of the stack from the corner of the window
+    pub offset: StaticStr,
+    /// Extra CSS styles to add
+    pub extra_css: StaticStr,
+}
+
+impl ToastCSS {
+    /// Initialize using the colors of the [crate::ColorPalette]
+    pub fn new() -> Self {
+        ToastCSS::default()
+    }
+
+    /// Build CSS to use to insert to the stylesheet
+    pub fn build(self) -> StaticCowStr {
+        StaticCowStr::Borrowed(
+            r#"
+        .pp-toasts {
+            position: fixed;
+            right: "#,
+        ) + self.offset
+            + ";
+            bottom: "
+            + self.offset
+            + r#";
+            z-index: 2147483100;
+            display: flex;
+            flex-direction: column;
+            gap: 8px;
+        }
+        .pp-toast {
+            display: flex;
+            align-items: center;
+            gap: 12px;
+            min-width: 240px;
+            padding: 10px 14px;
+            border-radius: 6px;
+            box-shadow: 0 2px 8px rgba(0, 0, 0, 0.25);
+            color: "#
+            + self.text_color
+            + r#";
+        }
+        .pp-toast-message {
+            flex: 1;
+        }
+        .pp-toast button {
+            border: none;
+            background: transparent;
+            color: inherit;
+            cursor: pointer;
+        }
+        .pp-toast-info {
+            background-color: "#
+            + self.info_color
+            + r#";
+        }
+        .pp-toast-success {
+            background-color: "#
+            + self.success_color
+            + ";
+            color: "
+            + self.success_text_color
+            + r#";
+        }
+        .pp-toast-warning {
+            background-color: "#
+            + self.warning_color
+            + ";
+            color: "
+            + self.warning_text_color
+            + r#";
+        }
+        .pp-toast-error {
+            background-color: "#
+            + self.error_color
+            + ";
+            color: "
+            + self.error_text_color
+            + r#";
+        }
+        "# + self.extra_css
+    }
+}
+
+impl Default for ToastCSS {
+    fn default() -> Self {
+        ToastCSS {
+            info_color: "var(--pp-tertiary)",
+            success_color: "var(--pp-success)",
+            warning_color: "var(--pp-warning)",
+            error_color: "var(--pp-error)",
+            text_color: "var(--pp-text)",
+            success_text_color: "var(--pp-on-success)",
+            warning_text_color: "var(--pp-on-warning)",
+            error_text_color: "var(--pp-on-error)",
+            offset: "16px",
+            extra_css: "",
+        }
+    }
+}
+
+#[cfg(test)]
+mod toast_checks {
+    use super::*;
+    use crate::ModifyView;
+
+    #[test]
+    fn renders() {
+        let toast = Toast::error("<disk> full")
+            .persistent()
+            .action("Retry", "retry");
+
+        assert_eq!(
+            concat!(
+                r#"<div class="pp-toast pp-toast-error" role="alert">"#,
+                r#"<span class="pp-toast-message">&lt;disk&gt; full</span>"#,
+                r#"<button class="pp-toast-action" data-pp-on-click="retry" type="button">Retry</button>"#,
+                r#"<button aria-label="Close" class="pp-toast-close" type="button">×</button></div>"#
+            ),
+            toast.to_html()
+        );
+        assert!(ModifyView::Toast(toast).to_html().ends_with(",null);"));
+        assert!(ModifyView::Toast(Toast::new("Saved"))
+            .to_html()
+            .ends_with(",5000);"));
+        assert!(ToastCSS::new()
+            .build()
+            .contains("background-color: var(--pp-error);"));
+    }
+}
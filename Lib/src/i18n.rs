@@ -0,0 +1,402 @@
+use crate::{utils::js_string, AssetFile, PuppeteerError, PuppeteerResult, StaticAsset};
+use camino::Utf8PathBuf;
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource};
+use std::sync::{Arc, RwLock};
+use unic_langid::{CharacterDirection, LanguageIdentifier};
+
+/// The locale used when the locale of the user cannot be detected
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+/// The direction of the text of a language
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum TextDirection {
+    /// Left to right like English
+    #[default]
+    Ltr,
+    /// Right to left like Arabic or Hebrew
+    Rtl,
+}
+
+impl TextDirection {
+    /// The value of the `dir` attribute
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ltr => "ltr",
+            Self::Rtl => "rtl",
+        }
+    }
+}
+
+struct LocalizationState {
+    locale: LanguageIdentifier,
+    fallback: LanguageIdentifier,
+    bundles: Vec<(LanguageIdentifier, FluentBundle<Arc<FluentResource>>)>,
+}
+
+/// Translates the messages of the app using [Fluent](https://projectfluent.org) `.ftl` resources.
+///
+/// Messages are looked up in the resources of the current locale, then in the resources
+/// of the same language for another region, like `fr-FR` for `fr-CA`, and then in the
+/// resources of the fallback locale. Plurals use the rules of the locale and numbers
+/// can be formatted using the `NUMBER()` function like `{ NUMBER($total, minimumFractionDigits: 2) }`.
+///
+/// The [Localization] of the [crate::ActiveAppEnv] detects the locale of the user and can be
+/// switched at runtime using [crate::ModifyView::SetLocale].
+///
+/// #### Example
+/// ```rust
+/// use puppeteer::{fluent_bundle::FluentArgs, Localization};
+///
+/// let localization = Localization::new("en-US")
+///     .unwrap()
+///     .add_resource("en-US", "emails = { $count ->\n [one] One email\n *[other] { $count } emails\n}")
+///     .unwrap();
+///
+/// let mut args = FluentArgs::new();
+/// args.set("count", 1);
+/// assert_eq!("One email", localization.format("emails", &args));
+/// ```
+#[derive(Clone)]
+pub struct Localization {
+    state: Arc<RwLock<LocalizationState>>,
+}
+
+impl Localization {
+    /// Create a localization using the `fallback` locale for messages that are not
+    /// translated. The current locale is the locale detected from the environment
+    pub fn new(fallback: &str) -> PuppeteerResult<Self> {
+        let fallback = Localization::parse_locale(fallback)?;
+        let locale = Localization::parse_locale(&Localization::detect_locale())
+            .unwrap_or_else(|_| fallback.clone());
+
+        Ok(Localization {
+            state: Arc::new(RwLock::new(LocalizationState {
+                locale,
+                fallback,
+                bundles: Vec::default(),
+            })),
+        })
+    }
+
+    /// The locale of the user as set by the operating system, or [DEFAULT_LOCALE]
+    pub fn detect_locale() -> String {
+        sys_locale::get_locale().unwrap_or_else(|| DEFAULT_LOCALE.to_owned())
+    }
+
+    /// Add the messages of a `.ftl` resource for the locale
+    pub fn add_resource(self, locale: &str, ftl: &str) -> PuppeteerResult<Self> {
+        let locale = Localization::parse_locale(locale)?;
+        let resource = FluentResource::try_new(ftl.to_owned()).map_err(|(_, errors)| {
+            PuppeteerError::InvalidFluentResource(
+                errors
+                    .iter()
+                    .map(|error| error.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            )
+        })?;
+
+        {
+            let mut state = self.write();
+            let position = match state.bundles.iter().position(|(id, _)| *id == locale) {
+                Some(position) => position,
+                None => {
+                    let mut bundle = FluentBundle::new_concurrent(vec![locale.clone()]);
+                    bundle.add_builtins().map_err(|error| {
+                        PuppeteerError::InvalidFluentResource(error.to_string())
+                    })?;
+                    state.bundles.push((locale, bundle));
+
+                    state.bundles.len() - 1
+                }
+            };
+
+            state.bundles[position]
+                .1
+                .add_resource(Arc::new(resource))
+                .map_err(|errors| {
+                    PuppeteerError::InvalidFluentResource(
+                        errors
+                            .iter()
+                            .map(|error| error.to_string())
+                            .collect::<Vec<String>>()
+                            .join(", "),
+                    )
+                })?;
+        }
+
+        Ok(self)
+    }
+
+    /// Add the messages of a `.ftl` file compiled into the app
+    pub fn add_static_asset(self, locale: &str, asset: &StaticAsset) -> PuppeteerResult<Self> {
+        let ftl = std::str::from_utf8(asset.bytes)
+            .map_err(|_| PuppeteerError::InvalidFluentResource(asset.name.to_owned()))?;
+
+        self.add_resource(locale, ftl)
+    }
+
+    /// Add the messages of a `.ftl` file loaded using [crate::AssetFileLoader]
+    pub fn add_asset_file(self, locale: &str, asset: &AssetFile<'_>) -> PuppeteerResult<Self> {
+        let ftl = std::str::from_utf8(&asset.bytes)
+            .map_err(|_| PuppeteerError::InvalidFluentResource(asset.name.to_string()))?;
+
+        self.add_resource(locale, ftl)
+    }
+
+    /// Load the resources in a directory where each locale either has a file like `fr-FR.ftl`
+    /// or a directory like `fr-FR/` containing `.ftl` files
+    pub async fn load_dir(mut self, dir: &str) -> PuppeteerResult<Self> {
+        let mut entries = smol::fs::read_dir(dir).await?;
+
+        while let Some(entry) = smol::stream::StreamExt::next(&mut entries).await {
+            let path = Utf8PathBuf::try_from(entry?.path())
+                .map_err(|error| PuppeteerError::InvalidFluentResource(error.to_string()))?;
+
+            if path.is_dir() {
+                let Some(locale) = path.file_name().map(str::to_owned) else {
+                    continue;
+                };
+                let mut files = smol::fs::read_dir(&path).await?;
+
+                while let Some(file) = smol::stream::StreamExt::next(&mut files).await {
+                    let file = Utf8PathBuf::try_from(file?.path()).map_err(|error| {
+                        PuppeteerError::InvalidFluentResource(error.to_string())
+                    })?;
+
+                    if file.extension() == Some("ftl") {
+                        self =
+                            self.add_resource(&locale, &smol::fs::read_to_string(&file).await?)?;
+                    }
+                }
+            } else if let (Some("ftl"), Some(locale)) = (path.extension(), path.file_stem()) {
+                let ftl = smol::fs::read_to_string(&path).await?;
+                self = self.add_resource(locale, &ftl)?;
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Change the current locale. Use [crate::ModifyView::SetLocale] to change it
+    /// and paint the view again
+    pub fn set_locale(&self, locale: &str) -> PuppeteerResult<()> {
+        self.write().locale = Localization::parse_locale(locale)?;
+
+        Ok(())
+    }
+
+    /// The current locale like `en-US`
+    pub fn locale(&self) -> String {
+        self.read().locale.to_string()
+    }
+
+    /// The locales that have resources
+    pub fn available_locales(&self) -> Vec<String> {
+        self.read()
+            .bundles
+            .iter()
+            .map(|(locale, _)| locale.to_string())
+            .collect()
+    }
+
+    /// The direction of the text of the current locale
+    pub fn direction(&self) -> TextDirection {
+        match self.read().locale.character_direction() {
+            CharacterDirection::RTL => TextDirection::Rtl,
+            _ => TextDirection::Ltr,
+        }
+    }
+
+    /// Translate a message without arguments. Returns the `id` if the message does not exist
+    pub fn message(&self, id: &str) -> String {
+        self.format(id, &FluentArgs::new())
+    }
+
+    /// Translate a message with arguments. Returns the `id` if the message does not exist
+    pub fn format(&self, id: &str, args: &FluentArgs) -> String {
+        self.translate(id, args).unwrap_or_else(|| {
+            tracing::warn!("MISSING TRANSLATION FOR `{id}`");
+
+            id.to_owned()
+        })
+    }
+
+    /// Translate a message without arguments. Returns the `fallback` without logging a warning
+    /// if the message does not exist, for messages that the app may not translate
+    pub fn message_or(&self, id: &str, fallback: &str) -> String {
+        self.translate(id, &FluentArgs::new())
+            .unwrap_or_else(|| fallback.to_owned())
+    }
+
+    fn translate(&self, id: &str, args: &FluentArgs) -> Option<String> {
+        let state = self.read();
+        let language = state.locale.language;
+
+        let candidates = state
+            .bundles
+            .iter()
+            .filter(|(locale, _)| *locale == state.locale)
+            .chain(
+                state
+                    .bundles
+                    .iter()
+                    .filter(|(locale, _)| *locale != state.locale && locale.language == language),
+            )
+            .chain(
+                state
+                    .bundles
+                    .iter()
+                    .filter(|(locale, _)| *locale == state.fallback),
+            );
+
+        for (_, bundle) in candidates {
+            let Some(pattern) = bundle.get_message(id).and_then(|message| message.value()) else {
+                continue;
+            };
+
+            let mut errors = Vec::new();
+            let outcome = bundle.format_pattern(pattern, Some(args), &mut errors);
+
+            if !errors.is_empty() {
+                tracing::warn!("ERRORS FORMATTING THE MESSAGE `{id}`: {errors:?}");
+            }
+
+            return Some(outcome.to_string());
+        }
+
+        None
+    }
+
+    /// The script that sets the `lang` and `dir` attributes of the document
+    pub(crate) fn document_script(&self) -> String {
+        String::from("document.documentElement.lang=")
+            + &js_string(&self.locale())
+            + ";document.documentElement.dir="
+            + &js_string(self.direction().as_str())
+            + ";"
+    }
+
+    fn parse_locale(locale: &str) -> PuppeteerResult<LanguageIdentifier> {
+        // Locales like `en_US.UTF-8` are used by POSIX environment variables
+        let locale = locale
+            .split('.')
+            .next()
+            .unwrap_or_default()
+            .replace('_', "-");
+
+        locale
+            .parse()
+            .map_err(|_| PuppeteerError::InvalidLocale(locale.to_owned()))
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, LocalizationState> {
+        self.state
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, LocalizationState> {
+        self.state
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for Localization {
+    fn default() -> Self {
+        Localization::new(DEFAULT_LOCALE).expect("The default locale is valid")
+    }
+}
+
+impl core::fmt::Debug for Localization {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let state = self.read();
+
+        f.debug_struct("Localization")
+            .field("locale", &state.locale.to_string())
+            .field("fallback", &state.fallback.to_string())
+            .field("available_locales", &self.available_locales())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod i18n_checks {
+    use super::*;
+    use crate::UiPaint;
+
+    const EN: &str = "
+hello = Hello { $name }
+cart = { $count ->
+    [one] One item
+   *[other] { $count } items
+}
+total = Total: { NUMBER($total, minimumFractionDigits: 2) }
+";
+    const FR: &str = "
+hello = Bonjour { $name }
+cart = { $count ->
+    [one] Un article
+   *[other] { $count } articles
+}
+";
+    const AR: &str = "hello = مرحبا { $name }";
+
+    #[test]
+    fn translates() {
+        let localization = Localization::new("en-US")
+            .unwrap()
+            .add_resource("en-US", EN)
+            .unwrap()
+            .add_resource("fr-FR", FR)
+            .unwrap()
+            .add_resource("ar", AR)
+            .unwrap();
+        let mut args = FluentArgs::new();
+        args.set("name", "Ada");
+        args.set("count", 1);
+        args.set("total", 5);
+
+        localization.set_locale("fr_CA.UTF-8").unwrap();
+        assert_eq!("fr-CA", localization.locale());
+        assert_eq!(
+            "Bonjour \u{2068}Ada\u{2069}",
+            localization.format("hello", &args)
+        );
+        assert_eq!("Un article", localization.format("cart", &args));
+        assert_eq!(
+            "Total: \u{2068}5.00\u{2069}",
+            localization.format("total", &args)
+        );
+        assert_eq!("missing", localization.message("missing"));
+        assert_eq!(TextDirection::Ltr, localization.direction());
+
+        args.set("count", 3);
+        localization.set_locale("en-GB").unwrap();
+        assert_eq!(
+            "\u{2068}3\u{2069} items",
+            localization.format("cart", &args)
+        );
+
+        localization.set_locale("ar-EG").unwrap();
+        assert_eq!(TextDirection::Rtl, localization.direction());
+        assert_eq!(
+            r#"document.documentElement.lang="ar-EG";document.documentElement.dir="rtl";"#,
+            localization.document_script()
+        );
+
+        assert!(crate::Shell::new()
+            .with_language(&localization.locale(), localization.direction())
+            .to_html()
+            .starts_with(
+                r#"<!DOCTYPE html><html lang="ar-EG" dir="rtl" data-pp-theme="light"><head>"#
+            ));
+
+        assert!(localization.set_locale("not a locale!").is_err());
+        assert!(Localization::new("en-US")
+            .unwrap()
+            .add_resource("en-US", "= broken")
+            .is_err());
+    }
+}
@@ -29,24 +29,78 @@ pub use logging::*;
 mod shell;
 pub use shell::*;
 
+mod color;
+pub use color::*;
+
+mod csp;
+pub use csp::*;
+
+mod fonts;
+pub use fonts::*;
+
+mod minify;
+pub use minify::*;
+
+mod font_metadata;
+pub use font_metadata::*;
+
 mod titlebar;
 pub use titlebar::*;
 
 mod ui_ops;
 pub use ui_ops::*;
 
+mod runtime;
+pub use runtime::*;
+
+mod vdom;
+pub use vdom::*;
+
+mod component;
+pub use component::*;
+
+mod router;
+pub use router::*;
+
+mod bindings;
+pub use bindings::*;
+
+mod i18n;
+pub use i18n::*;
+
+mod modal;
+pub use modal::*;
+
+mod virtual_list;
+pub use virtual_list::*;
+
+#[cfg(any(test, feature = "testing"))]
+mod dom_simulator;
+#[cfg(any(test, feature = "testing"))]
+pub use dom_simulator::*;
+
 mod html_utils;
 pub use html_utils::*;
 
 mod assets;
 pub use assets::*;
 
+mod clipboard;
+pub use clipboard::*;
+
+mod shortcuts;
+pub use shortcuts::*;
+
+mod dev_mode;
+pub use dev_mode::*;
+
 mod utils;
 
 /// Reuse crates in the lib for better compatibility
 //
 pub use arrayvec;
 pub use async_trait;
+pub use fluent_bundle;
 pub use smol;
 pub use thiserror;
 pub use tracing;
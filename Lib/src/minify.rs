@@ -0,0 +1,329 @@
+/// Minify a stylesheet by removing the comments and the whitespace that does not change
+/// the meaning of the CSS. Strings are kept as they are.
+/// Use it in a `build.rs` to minify the styles at build time or [crate::Shell::minify]
+/// to minify all the styles of the [crate::Shell] when the app starts
+pub fn minify_css(css: &str) -> String {
+    let mut outcome = String::with_capacity(css.len());
+    let mut characters = css.chars().peekable();
+    let mut pending_space = false;
+
+    while let Some(character) = characters.next() {
+        match character {
+            '"' | '\'' => {
+                push_space(&mut outcome, &mut pending_space, character);
+                copy_string(&mut outcome, &mut characters, character);
+            }
+            '/' if characters.peek() == Some(&'*') => {
+                characters.next();
+                skip_block_comment(&mut characters);
+                pending_space = true;
+            }
+            _ if character.is_whitespace() => pending_space = true,
+            '}' => {
+                if outcome.ends_with(';') {
+                    outcome.pop();
+                }
+
+                pending_space = false;
+                outcome.push(character);
+            }
+            _ => {
+                push_space(&mut outcome, &mut pending_space, character);
+                outcome.push(character);
+            }
+        }
+    }
+
+    outcome
+}
+
+/// Minify a script by removing the comments, the indentation and the empty lines.
+/// Line breaks are kept so that automatic semicolon insertion still works.
+/// Strings, template literals and regular expressions are kept as they are.
+/// Use it in a `build.rs` to minify the scripts at build time or [crate::Shell::minify]
+/// to minify all the scripts of the [crate::Shell] when the app starts
+pub fn minify_js(js: &str) -> String {
+    let mut outcome = String::with_capacity(js.len());
+    let mut characters = js.chars().peekable();
+    let mut pending_space = false;
+
+    while let Some(character) = characters.next() {
+        match character {
+            '"' | '\'' | '`' => {
+                push_js_space(&mut outcome, &mut pending_space);
+                copy_string(&mut outcome, &mut characters, character);
+            }
+            '/' if characters.peek() == Some(&'/') => {
+                characters.by_ref().take_while(|next| *next != '\n').count();
+                push_line_break(&mut outcome, &mut pending_space);
+            }
+            // A comment containing a line break ends the line for automatic semicolon insertion
+            '/' if characters.peek() == Some(&'*') => {
+                characters.next();
+
+                if skip_block_comment(&mut characters) {
+                    push_line_break(&mut outcome, &mut pending_space);
+                } else {
+                    pending_space = true;
+                }
+            }
+            '/' if starts_regex(&outcome) => {
+                push_js_space(&mut outcome, &mut pending_space);
+                copy_regex(&mut outcome, &mut characters);
+            }
+            '\n' | '\r' => push_line_break(&mut outcome, &mut pending_space),
+            _ if character.is_whitespace() => pending_space = true,
+            _ => {
+                push_js_space(&mut outcome, &mut pending_space);
+                outcome.push(character);
+            }
+        }
+    }
+
+    outcome.trim_end().to_owned()
+}
+
+/// Minify the content of each `<script>` element of the `html` using [minify_js].
+/// Elements with a `src` have no content and elements whose `type` is not JavaScript,
+/// like JSON, templates and import maps, are kept as they are
+pub fn minify_script_elements(html: &str) -> String {
+    let mut outcome = String::with_capacity(html.len());
+    let mut rest = html.trim();
+
+    while let Some(start) = rest.find("<script") {
+        let Some(open_end) = rest[start..].find('>').map(|index| start + index + 1) else {
+            break;
+        };
+        let Some(close) = rest[open_end..]
+            .find("</script")
+            .map(|index| open_end + index)
+        else {
+            break;
+        };
+
+        outcome.push_str(rest[..open_end].trim());
+
+        if is_javascript(&rest[start..open_end]) {
+            outcome.push_str(&minify_js(&rest[open_end..close]));
+        } else {
+            outcome.push_str(&rest[open_end..close]);
+        }
+
+        rest = &rest[close..];
+
+        let close_end = rest.find('>').map(|index| index + 1).unwrap_or(rest.len());
+        outcome.push_str(&rest[..close_end]);
+        rest = rest[close_end..].trim_start();
+    }
+
+    outcome.push_str(rest);
+
+    outcome
+}
+
+/// Whether the `type` of the opening tag of a script is empty, `module` or a JavaScript MIME type
+fn is_javascript(opening_tag: &str) -> bool {
+    let lowercase = opening_tag.to_ascii_lowercase();
+    let Some(index) = lowercase.find(" type=") else {
+        return true;
+    };
+    let value = lowercase[index + " type=".len()..].trim_start_matches(['"', '\'']);
+    let end = value
+        .find(|character: char| {
+            character == '"' || character == '\'' || character == '>' || character.is_whitespace()
+        })
+        .unwrap_or(value.len());
+    let script_type = value[..end].trim();
+
+    script_type.is_empty()
+        || script_type == "module"
+        || matches!(
+            script_type.split(';').next().unwrap_or_default().trim(),
+            "text/javascript"
+                | "application/javascript"
+                | "text/ecmascript"
+                | "application/ecmascript"
+                | "application/x-javascript"
+                | "text/jscript"
+        )
+}
+
+/// Whitespace is only needed in CSS between two words like `solid 1px` or
+/// descendant selectors like `nav a`
+fn push_space(outcome: &mut String, pending_space: &mut bool, next: char) {
+    if core::mem::take(pending_space)
+        && !outcome.is_empty()
+        && !outcome.ends_with(['{', '}', ';', ':', ',', '>', '(', ' '])
+        && !matches!(next, '{' | '}' | ';' | ',' | '>' | ')' | '!')
+    {
+        outcome.push(' ');
+    }
+}
+
+/// Whitespace is kept as a single space inside a line of JavaScript
+fn push_js_space(outcome: &mut String, pending_space: &mut bool) {
+    if core::mem::take(pending_space) && !outcome.is_empty() && !outcome.ends_with('\n') {
+        outcome.push(' ');
+    }
+}
+
+fn push_line_break(outcome: &mut String, pending_space: &mut bool) {
+    *pending_space = false;
+
+    let trimmed = outcome.trim_end_matches([' ', '\t']).len();
+    outcome.truncate(trimmed);
+
+    if !outcome.is_empty() && !outcome.ends_with('\n') {
+        outcome.push('\n');
+    }
+}
+
+/// A `/` starts a regular expression instead of a division after an operator,
+/// an opening bracket or at the start of a statement
+fn starts_regex(outcome: &str) -> bool {
+    let before = outcome.trim_end();
+
+    match before.chars().last() {
+        None => true,
+        Some(last) if "(,=:[!&|?{};+-*%<>~^".contains(last) => true,
+        Some(_) => ["return", "typeof", "case", "do", "else", "in", "of", "void"]
+            .iter()
+            .any(|keyword| {
+                before.strip_suffix(keyword).is_some_and(|start| {
+                    !start.ends_with(|character: char| {
+                        character.is_alphanumeric() || character == '_' || character == '$'
+                    })
+                })
+            }),
+    }
+}
+
+fn copy_string(
+    outcome: &mut String,
+    characters: &mut core::iter::Peekable<core::str::Chars>,
+    quote: char,
+) {
+    outcome.push(quote);
+
+    while let Some(character) = characters.next() {
+        outcome.push(character);
+
+        if character == '\\' {
+            if let Some(escaped) = characters.next() {
+                outcome.push(escaped);
+            }
+        } else if character == quote {
+            break;
+        }
+    }
+}
+
+fn copy_regex(outcome: &mut String, characters: &mut core::iter::Peekable<core::str::Chars>) {
+    outcome.push('/');
+    let mut in_class = false;
+
+    while let Some(character) = characters.next() {
+        outcome.push(character);
+
+        match character {
+            '\\' => {
+                if let Some(escaped) = characters.next() {
+                    outcome.push(escaped);
+                }
+            }
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '/' if !in_class => break,
+            '\n' => break,
+            _ => (),
+        }
+    }
+}
+
+/// Returns whether the comment contained a line break
+fn skip_block_comment(characters: &mut core::iter::Peekable<core::str::Chars>) -> bool {
+    let mut previous = '\0';
+    let mut line_break = false;
+
+    for character in characters.by_ref() {
+        if previous == '*' && character == '/' {
+            break;
+        }
+
+        line_break |= matches!(character, '\n' | '\r' | '\u{2028}' | '\u{2029}');
+        previous = character;
+    }
+
+    line_break
+}
+
+#[cfg(test)]
+mod minify_checks {
+    use super::*;
+
+    #[test]
+    fn minifies_css_and_js() {
+        let css = r#"
+            /* The navigation */
+            nav a , nav  button:hover {
+                margin: 0 auto ;
+                font-family: "Sans  Pro", serif;
+                width: calc(100% - 2px) !important;
+            }
+            ul > li::before { content: "/* kept */"; }
+        "#;
+        assert_eq!(
+            r#"nav a,nav button:hover{margin:0 auto;font-family:"Sans  Pro",serif;width:calc(100% - 2px)!important}ul>li::before{content:"/* kept */"}"#,
+            minify_css(css)
+        );
+
+        let js = r#"
+            // Greet the user
+            const greeting = 'Hello  // there';   /* inline */
+            const pattern = /\/\/+[/]/g;
+
+            function ratio(a, b) {
+                return a / b / 2;
+            }
+            const html = `<p>
+  ${greeting}</p>`;
+        "#;
+        assert_eq!(
+            concat!(
+                "const greeting = 'Hello  // there';\n",
+                "const pattern = /\\/\\/+[/]/g;\n",
+                "function ratio(a, b) {\n",
+                "return a / b / 2;\n",
+                "}\n",
+                "const html = `<p>\n  ${greeting}</p>`;",
+            ),
+            minify_js(js)
+        );
+
+        assert_eq!(
+            r#"<script src="/a.js"></script><script type="module">import a from '/a.js'"#.to_owned()
+                + "\na()</script>",
+            minify_script_elements(
+                "\n<script src=\"/a.js\"></script>\n<script type=\"module\">\n    import a from '/a.js'\n    a() // run\n</script>\n"
+            )
+        );
+
+        assert_eq!(
+            "function f() {\nreturn\nx\n}\nconst y = a + b",
+            minify_js("function f() {\n    return /*\n    */ x\n}\nconst y = a /* sum */ + b")
+        );
+
+        let data = concat!(
+            r#"<script type="application/json">{ "a" :  1 } // kept</script>"#,
+            r#"<script type='importmap'>{ "imports":  {} }</script>"#,
+            r#"<script type="text/x-template"><p>  // kept </p></script>"#,
+        );
+        assert_eq!(data, minify_script_elements(data));
+        assert_eq!(
+            r#"<script type="text/javascript; charset=utf-8">a()</script><script>b()</script>"#,
+            minify_script_elements(
+                "<script type=\"text/javascript; charset=utf-8\">\n  a() // run\n</script><script>\n  b()\n</script>"
+            )
+        );
+    }
+}
@@ -0,0 +1,433 @@
+use crate::{Html, Localization, ModifyView, StaticCowStr, StaticStr, UiPaint, VNode};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// The prefix of the IPC messages sent when the user closes a modal.
+/// The full message is `puppeteer:modal>{id}>{action}>{value}`
+pub const MODAL_IPC_PREFIX: &str = "puppeteer:modal>";
+
+/// The action sent when the user clicks the OK button of a modal
+pub const MODAL_OK: &str = "ok";
+
+/// The action sent when the user clicks the cancel button of a modal or presses `Escape`
+pub const MODAL_CANCEL: &str = "cancel";
+
+/// The message ID of the label of the OK button, `OK` if it is not translated
+pub const MODAL_OK_LABEL: &str = "puppeteer-modal-ok";
+
+/// The message ID of the label of the cancel button, `Cancel` if it is not translated
+pub const MODAL_CANCEL_LABEL: &str = "puppeteer-modal-cancel";
+
+/// How the user closed a modal
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct ModalResponse {
+    /// The ID of the modal
+    pub id: u64,
+    /// The `data-pp-modal-action` of the element that was clicked,
+    /// [MODAL_OK] if `Enter` was pressed in the input or [MODAL_CANCEL] if `Escape` was pressed
+    pub action: String,
+    /// The value of the element with the `data-pp-modal-input` attribute or an empty string
+    pub value: String,
+}
+
+impl ModalResponse {
+    /// Returns `None` if the IPC message is not a modal response
+    pub fn from_ipc(message: &str) -> Option<Self> {
+        let mut parts = message.strip_prefix(MODAL_IPC_PREFIX)?.splitn(3, '>');
+
+        Some(ModalResponse {
+            id: parts.next()?.parse().ok()?,
+            action: parts.next()?.to_owned(),
+            value: parts.next()?.to_owned(),
+        })
+    }
+
+    /// Was the modal closed using the OK button or by pressing `Enter`
+    pub fn is_ok(&self) -> bool {
+        self.action == MODAL_OK
+    }
+
+    fn cancelled(id: u64) -> Self {
+        ModalResponse {
+            id,
+            action: MODAL_CANCEL.to_owned(),
+            value: String::default(),
+        }
+    }
+}
+
+type Painter = Arc<dyn Fn(ModifyView) -> bool + Send + Sync>;
+
+#[derive(Default)]
+struct ModalsState {
+    next_id: u64,
+    pending: HashMap<u64, smol::channel::Sender<ModalResponse>>,
+    painter: Option<Painter>,
+    localization: Option<Localization>,
+}
+
+/// Opens modal dialogs that the event handlers can wait on.
+///
+/// The modal is painted into an overlay styled by [ModalCSS] that traps the focus
+/// until the user clicks an element with a `data-pp-modal-action` attribute or presses `Escape`.
+/// The future resolves when the response is received through IPC.
+/// The labels of the buttons are the [MODAL_OK_LABEL] and [MODAL_CANCEL_LABEL] messages
+/// of the [Localization] of the app.
+///
+/// #### Example
+/// ```rust,ignore
+/// async fn event_handler(&mut self, app_env: &ActiveAppEnv) -> ModifyView {
+///     if app_env.modals.confirm("Delete the file?").await {
+///         // Delete the file
+///     }
+///
+///     ModifyView::Skip
+/// }
+/// ```
+#[derive(Default, Clone)]
+pub struct Modals {
+    state: Arc<Mutex<ModalsState>>,
+}
+
+impl Modals {
+    /// Create the modals. Modals are only painted once the app has started
+    pub fn new() -> Self {
+        Modals::default()
+    }
+
+    /// Show a message with an OK button
+    pub async fn alert(&self, message: &str) {
+        self.open(
+            Html::div()
+                .child(
+                    Html::p()
+                        .class("pp-modal-message")
+                        .child(Html::text(message)),
+                )
+                .child(self.actions(&[(MODAL_OK_LABEL, "OK", MODAL_OK)])),
+        )
+        .await;
+    }
+
+    /// Ask the user to confirm. Returns `true` if the OK button was clicked
+    pub async fn confirm(&self, message: &str) -> bool {
+        self.open(
+            Html::div()
+                .child(
+                    Html::p()
+                        .class("pp-modal-message")
+                        .child(Html::text(message)),
+                )
+                .child(self.actions(&[
+                    (MODAL_CANCEL_LABEL, "Cancel", MODAL_CANCEL),
+                    (MODAL_OK_LABEL, "OK", MODAL_OK),
+                ])),
+        )
+        .await
+        .is_ok()
+    }
+
+    /// Ask the user for a text. Returns `None` if the modal was cancelled
+    pub async fn prompt(&self, message: &str, default_value: &str) -> Option<String> {
+        let response = self
+            .open(
+                Html::div()
+                    .child(
+                        Html::label()
+                            .class("pp-modal-message")
+                            .child(Html::text(message))
+                            .child(
+                                Html::input()
+                                    .attr("type", "text")
+                                    .attr("value", default_value)
+                                    .attr("data-pp-modal-input", ""),
+                            ),
+                    )
+                    .child(self.actions(&[
+                        (MODAL_CANCEL_LABEL, "Cancel", MODAL_CANCEL),
+                        (MODAL_OK_LABEL, "OK", MODAL_OK),
+                    ])),
+            )
+            .await;
+
+        response.is_ok().then_some(response.value)
+    }
+
+    /// Show custom content. Elements with a `data-pp-modal-action` attribute, see
+    /// [VNode::modal_action], close the modal and the value of the element with a
+    /// `data-pp-modal-input` attribute is sent with the response.
+    /// If the app has not started, the modal is cancelled
+    pub async fn open(&self, content: impl UiPaint) -> ModalResponse {
+        let (sender, receiver) = smol::channel::bounded(1);
+
+        let (id, painter) = {
+            let mut state = self.lock();
+            let id = state.next_id;
+            state.next_id += 1;
+            state.pending.insert(id, sender);
+
+            (id, state.painter.clone())
+        };
+
+        let painted = painter.is_some_and(|painter| {
+            painter(ModifyView::ShowModal {
+                id,
+                content: content.to_html().to_string(),
+            })
+        });
+
+        if !painted {
+            tracing::warn!("COULD NOT PAINT THE MODAL `{id}`");
+            self.lock().pending.remove(&id);
+
+            return ModalResponse::cancelled(id);
+        }
+
+        receiver
+            .recv()
+            .await
+            .unwrap_or_else(|_| ModalResponse::cancelled(id))
+    }
+
+    /// Resolve the future of the modal with the response sent by the webview
+    pub fn resolve(&self, response: ModalResponse) {
+        if let Some(sender) = self.lock().pending.remove(&response.id) {
+            // The receiver is dropped if the handler waiting on the modal was cancelled
+            sender.try_send(response).ok();
+        }
+    }
+
+    /// The number of modals waiting for the user
+    pub fn pending(&self) -> usize {
+        self.lock().pending.len()
+    }
+
+    /// Set how the modals are painted. Returns `false` if the modal could not be painted
+    pub(crate) fn set_painter(&self, painter: impl Fn(ModifyView) -> bool + Send + Sync + 'static) {
+        self.lock().painter.replace(Arc::new(painter));
+    }
+
+    /// Set the [Localization] used to translate the labels of the buttons
+    pub(crate) fn set_localization(&self, localization: Localization) {
+        self.lock().localization.replace(localization);
+    }
+
+    /// The buttons with the message ID of their label, the English label and the action
+    fn actions(&self, buttons: &[(&str, &str, &str)]) -> VNode {
+        let localization = self.lock().localization.clone();
+
+        Html::div().class("pp-modal-actions").children(
+            buttons
+                .iter()
+                .map(|(label_id, label, action)| {
+                    let label = localization.as_ref().map_or_else(
+                        || label.to_string(),
+                        |localization| localization.message_or(label_id, label),
+                    );
+
+                    Html::button()
+                        .attr("type", "button")
+                        .modal_action(action)
+                        .child(Html::text(&label))
+                })
+                .collect::<Vec<VNode>>(),
+        )
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ModalsState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl core::fmt::Debug for Modals {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Modals")
+            .field("pending", &self.pending())
+            .finish()
+    }
+}
+
+impl VNode {
+    /// Close the modal containing the element when it is clicked, responding with the `action`.
+    /// The action must not contain `>`
+    pub fn modal_action(self, action: &str) -> Self {
+        self.attr("data-pp-modal-action", action)
+    }
+}
+
+/// Create CSS for the modals based on the CSS custom properties of the [crate::ColorPalette]
+#[derive(Debug, PartialEq, Eq, Clone, PartialOrd, Ord)]
+pub struct ModalCSS {
+    /// The color of the overlay covering the app
+    pub overlay_color: StaticStr,
+    /// The background color of the modal
+    pub background_color: StaticStr,
+    /// The color of the text of the modal
+    pub text_color: StaticStr,
+    /// The background color of the buttons
+    pub button_color: StaticStr,
+    /// The color of the text of the buttons
+    pub button_text_color: StaticStr,
+    /// The padding inside the modal
+    pub padding: StaticStr,
+    /// The radius of the corners of the modal and the buttons
+    pub border_radius: StaticStr,
+    /// Extra CSS styles to add
+    pub extra_css: StaticStr,
+}
+
+impl ModalCSS {
+    /// Initialize using the colors of the [crate::ColorPalette]
+    pub fn new() -> Self {
+        ModalCSS::default()
+    }
+
+    /// Build CSS to use to insert to the stylesheet
+    pub fn build(self) -> StaticCowStr {
+        StaticCowStr::Borrowed(
+            r#"
+        .pp-modal-overlay {
+            position: fixed;
+            inset: 0;
+            z-index: 2147483000;
+            display: flex;
+            align-items: center;
+            justify-content: center;
+            background-color: "#,
+        ) + self.overlay_color
+            + r#";
+        }
+        .pp-modal {
+            min-width: 280px;
+            max-width: 80vw;
+            max-height: 80vh;
+            overflow: auto;
+            padding: "#
+            + self.padding
+            + ";
+            border-radius: "
+            + self.border_radius
+            + ";
+            background-color: "
+            + self.background_color
+            + ";
+            color: "
+            + self.text_color
+            + r#";
+        }
+        .pp-modal-actions {
+            display: flex;
+            justify-content: end;
+            gap: 8px;
+            margin-top: 16px;
+        }
+        .pp-modal-actions button {
+            border: none;
+            padding: 6px 16px;
+            border-radius: "#
+            + self.border_radius
+            + ";
+            background-color: "
+            + self.button_color
+            + ";
+            color: "
+            + self.button_text_color
+            + r#";
+        }
+        .pp-modal input {
+            display: block;
+            width: 100%;
+            margin-top: 8px;
+        }
+        "# + self.extra_css
+    }
+}
+
+impl Default for ModalCSS {
+    fn default() -> Self {
+        ModalCSS {
+            overlay_color: "rgba(0, 0, 0, 0.5)",
+            background_color: "var(--pp-surface)",
+            text_color: "var(--pp-text)",
+            button_color: "var(--pp-tertiary)",
+            button_text_color: "var(--pp-text)",
+            padding: "20px",
+            border_radius: "6px",
+            extra_css: "",
+        }
+    }
+}
+
+#[cfg(test)]
+mod modal_checks {
+    use super::*;
+
+    #[test]
+    fn resolves() {
+        let modals = Modals::new();
+        assert!(!smol::block_on(modals.confirm("Delete?")));
+        assert_eq!(0, modals.pending());
+
+        let painted = Arc::new(Mutex::new(Vec::<ModifyView>::new()));
+        let resolver = modals.clone();
+        let log = painted.clone();
+        modals.set_painter(move |view| {
+            if let ModifyView::ShowModal { id, .. } = &view {
+                resolver.resolve(
+                    ModalResponse::from_ipc(&format!("{MODAL_IPC_PREFIX}{id}>ok>a>b"))
+                        .expect("Modal response"),
+                );
+            }
+            log.lock().unwrap().push(view);
+
+            true
+        });
+
+        assert_eq!(
+            Some("a>b".to_owned()),
+            smol::block_on(modals.prompt("Name", "<none>"))
+        );
+        assert!(smol::block_on(modals.confirm("Delete?")));
+        assert_eq!(0, modals.pending());
+
+        modals.set_localization(
+            Localization::new("en")
+                .and_then(|localization| {
+                    localization.add_resource("fr", "puppeteer-modal-cancel = Annuler")
+                })
+                .unwrap(),
+        );
+        modals
+            .lock()
+            .localization
+            .as_ref()
+            .unwrap()
+            .set_locale("fr")
+            .unwrap();
+        assert!(smol::block_on(modals.confirm("Supprimer ?")));
+
+        let painted = painted.lock().unwrap();
+        assert_eq!(3, painted.len());
+        assert!(painted[1].to_html().contains(r#"\u003eOK\u003c/button"#));
+        assert!(painted[2]
+            .to_html()
+            .contains(r#"\u003eAnnuler\u003c/button"#));
+        assert!(painted[2].to_html().contains(r#"\u003eOK\u003c/button"#));
+        assert!(painted[0]
+            .to_html()
+            .starts_with("window.puppeteer.showModal(1,"));
+        assert!(painted[0].to_html().contains(r#"value=\"&lt;none&gt;\""#));
+        assert!(painted[1]
+            .to_html()
+            .contains(r#"data-pp-modal-action=\"cancel\""#));
+
+        assert_eq!(None, ModalResponse::from_ipc("puppeteer:modal>x>ok>"));
+        assert!(ModalCSS::new()
+            .build()
+            .contains("background-color: var(--pp-surface);"));
+    }
+}
@@ -0,0 +1,517 @@
+use crate::{
+    utils::js_string, ActiveAppEnv, AppEnvironment, ModifyView, OsType, PuppeteerError,
+    PuppeteerResult, VNode,
+};
+use async_trait::async_trait;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+/// The prefix of the IPC messages handled by the [Router].
+/// The messages are `puppeteer:route>navigate>{path}`, `puppeteer:route>back`
+/// and `puppeteer:route>forward`
+pub const ROUTE_IPC_PREFIX: &str = "puppeteer:route>";
+
+/// The maximum number of redirects followed by a single navigation
+pub const MAX_ROUTE_REDIRECTS: usize = 8;
+
+/// Goes back using the mouse back button or the back shortcut, forward using the mouse forward
+/// button or the forward shortcut and navigates when an element with a `data-pp-route` attribute
+/// is clicked. `PUPPETEER_NAVIGATION_KEYS` is replaced by the shortcuts, see [Router::script]
+const ROUTER_SCRIPT: &str = r#"
+<script>
+document.addEventListener('mouseup', (e) => {
+    if (e.button === 3) {
+        e.preventDefault();
+        window.ipc.postMessage('puppeteer:route>back');
+    } else if (e.button === 4) {
+        e.preventDefault();
+        window.ipc.postMessage('puppeteer:route>forward');
+    }
+});
+document.addEventListener('keydown', (e) => {
+    const keys = PUPPETEER_NAVIGATION_KEYS;
+    const modifiers = ['altKey', 'ctrlKey', 'metaKey', 'shiftKey'].filter((modifier) => e[modifier]);
+    const editable = e.target instanceof Element
+        && (e.target.isContentEditable || e.target.closest('input, textarea, select') !== null);
+
+    if (editable || modifiers.length !== 1 || modifiers[0] !== keys.modifier) {
+        return;
+    }
+
+    if (e.code === keys.back) {
+        e.preventDefault();
+        window.ipc.postMessage('puppeteer:route>back');
+    } else if (e.code === keys.forward) {
+        e.preventDefault();
+        window.ipc.postMessage('puppeteer:route>forward');
+    }
+});
+document.addEventListener('click', (e) => {
+    const link = e.target.closest('[data-pp-route]');
+
+    if (link !== null) {
+        e.preventDefault();
+        window.ipc.postMessage('puppeteer:route>navigate>' + link.dataset.ppRoute);
+    }
+});
+</script>
+"#;
+
+/// The outcome of [Route::guard]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub enum RouteGuard {
+    /// Load and paint the route
+    Allow,
+    /// Stay on the current page
+    Deny,
+    /// Navigate to another path instead
+    Redirect(String),
+}
+
+/// The path being navigated to
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Default)]
+pub struct RouteRequest {
+    /// The path without the query string like `/users/7`
+    pub path: String,
+    /// The parameters of the route pattern like `id` for `/users/:id`
+    pub params: BTreeMap<String, String>,
+    /// The parameters of the query string like `tab` for `/users/7?tab=posts`
+    pub query: BTreeMap<String, String>,
+}
+
+impl RouteRequest {
+    /// Get a parameter of the route pattern
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(String::as_str)
+    }
+
+    /// Get a parameter of the query string
+    pub fn query(&self, name: &str) -> Option<&str> {
+        self.query.get(name).map(String::as_str)
+    }
+}
+
+/// A page of the app registered in a [Router]
+#[async_trait]
+pub trait Route: Send + Sync + 'static {
+    /// Runs before the route is loaded, for example to check that the user is logged in.
+    /// This defaults to [RouteGuard::Allow]
+    async fn guard(&self, _request: &RouteRequest, _app_env: &ActiveAppEnv) -> RouteGuard {
+        RouteGuard::Allow
+    }
+
+    /// Load the data of the page and return the [ModifyView] painting it,
+    /// usually a [ModifyView::ReplaceApp]
+    async fn load(
+        &self,
+        request: &RouteRequest,
+        app_env: &ActiveAppEnv,
+    ) -> PuppeteerResult<ModifyView>;
+}
+
+#[derive(Clone)]
+struct RouteEntry {
+    segments: Vec<String>,
+    route: Arc<dyn Route>,
+}
+
+impl RouteEntry {
+    fn matches(&self, segments: &[&str]) -> Option<BTreeMap<String, String>> {
+        if self.segments.len() != segments.len() {
+            return None;
+        }
+
+        let mut params = BTreeMap::new();
+
+        for (pattern, segment) in self.segments.iter().zip(segments.iter()) {
+            match pattern.strip_prefix(':') {
+                Some(name) => {
+                    params.insert(name.to_owned(), (*segment).to_owned());
+                }
+                None if pattern == segment => (),
+                None => return None,
+            }
+        }
+
+        Some(params)
+    }
+}
+
+#[derive(Debug, Default)]
+struct History {
+    entries: Vec<String>,
+    index: usize,
+}
+
+/// Changes the page painted in the `puppeteer_app` mount point and keeps the history
+/// of the visited pages so that the user can go back and forward.
+///
+/// Route patterns are paths like `/users/:id` where segments starting with `:`
+/// are parameters. Navigating to a path runs the [Route::guard] and then the
+/// [Route::load] of the first matching route before painting it.
+#[derive(Default, Clone)]
+pub struct Router {
+    routes: Vec<RouteEntry>,
+    history: Arc<Mutex<History>>,
+}
+
+impl Router {
+    /// Create a router without any route
+    pub fn new() -> Self {
+        Router::default()
+    }
+
+    /// Register a route. Routes are matched in the order they are registered
+    pub fn route(mut self, pattern: &str, route: impl Route) -> Self {
+        self.routes.push(RouteEntry {
+            segments: Router::segments(pattern)
+                .into_iter()
+                .map(str::to_owned)
+                .collect(),
+            route: Arc::new(route),
+        });
+
+        self
+    }
+
+    /// The script added to the [crate::Shell] when routes are registered.
+    /// The back and forward shortcuts are `Cmd+[` and `Cmd+]` on macOS and iOS and
+    /// `Alt+Left` and `Alt+Right` on other platforms, like browsers. They are ignored
+    /// while editing text since they also move the cursor
+    pub fn script(env: &AppEnvironment) -> String {
+        let (modifier, back, forward) = match env.os {
+            OsType::MacOs | OsType::Ios => ("metaKey", "BracketLeft", "BracketRight"),
+            _ => ("altKey", "ArrowLeft", "ArrowRight"),
+        };
+        let keys = String::from("{modifier:")
+            + &js_string(modifier)
+            + ",back:"
+            + &js_string(back)
+            + ",forward:"
+            + &js_string(forward)
+            + "}";
+
+        ROUTER_SCRIPT.replace("PUPPETEER_NAVIGATION_KEYS", &keys)
+    }
+
+    /// Are any routes registered
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// The path of the current page
+    pub fn current(&self) -> Option<String> {
+        let history = self.lock();
+
+        history.entries.get(history.index).cloned()
+    }
+
+    /// Is there a page before the current page in the history
+    pub fn can_go_back(&self) -> bool {
+        self.lock().index > 0
+    }
+
+    /// Is there a page after the current page in the history
+    pub fn can_go_forward(&self) -> bool {
+        let history = self.lock();
+
+        history.index + 1 < history.entries.len()
+    }
+
+    /// Navigate to the path, removing the pages after the current page from the history.
+    /// Returns [ModifyView::Skip] if a guard denied the navigation
+    pub async fn navigate(
+        &self,
+        path: &str,
+        app_env: &ActiveAppEnv,
+    ) -> PuppeteerResult<ModifyView> {
+        match self.resolve(path, app_env).await? {
+            Some((path, view)) => {
+                self.push(path);
+
+                Ok(view)
+            }
+            None => Ok(ModifyView::Skip),
+        }
+    }
+
+    /// Go to the previous page in the history. Returns [ModifyView::Skip] if there is none
+    pub async fn back(&self, app_env: &ActiveAppEnv) -> PuppeteerResult<ModifyView> {
+        let target = {
+            let history = self.lock();
+
+            history
+                .index
+                .checked_sub(1)
+                .and_then(|index| Some((index, history.entries.get(index)?.clone())))
+        };
+
+        self.go_to(target, app_env).await
+    }
+
+    /// Go to the next page in the history. Returns [ModifyView::Skip] if there is none
+    pub async fn forward(&self, app_env: &ActiveAppEnv) -> PuppeteerResult<ModifyView> {
+        let target = {
+            let history = self.lock();
+            let index = history.index + 1;
+
+            history.entries.get(index).map(|path| (index, path.clone()))
+        };
+
+        self.go_to(target, app_env).await
+    }
+
+    /// Load the current page again without changing the history, for example after
+    /// the locale changed. Returns [ModifyView::Skip] if no page was loaded
+    pub async fn reload(&self, app_env: &ActiveAppEnv) -> PuppeteerResult<ModifyView> {
+        let target = {
+            let history = self.lock();
+
+            history
+                .entries
+                .get(history.index)
+                .map(|path| (history.index, path.clone()))
+        };
+
+        self.go_to(target, app_env).await
+    }
+
+    async fn go_to(
+        &self,
+        target: Option<(usize, String)>,
+        app_env: &ActiveAppEnv,
+    ) -> PuppeteerResult<ModifyView> {
+        let Some((index, path)) = target else {
+            return Ok(ModifyView::Skip);
+        };
+
+        match self.resolve(&path, app_env).await? {
+            Some((resolved, view)) => {
+                if resolved == path {
+                    self.lock().index = index;
+                } else {
+                    self.push(resolved);
+                }
+
+                Ok(view)
+            }
+            None => Ok(ModifyView::Skip),
+        }
+    }
+
+    /// Runs the guards, following redirects, and the loader of the matching route.
+    /// Returns the path that was loaded or `None` if a guard denied the navigation
+    async fn resolve(
+        &self,
+        path: &str,
+        app_env: &ActiveAppEnv,
+    ) -> PuppeteerResult<Option<(String, ModifyView)>> {
+        let mut path = path.to_owned();
+
+        for _ in 0..=MAX_ROUTE_REDIRECTS {
+            let (route, request) = self.find(&path)?;
+
+            match route.guard(&request, app_env).await {
+                RouteGuard::Allow => {
+                    let view = route.load(&request, app_env).await?;
+
+                    return Ok(Some((path, view)));
+                }
+                RouteGuard::Deny => return Ok(None),
+                RouteGuard::Redirect(to) => path = to,
+            }
+        }
+
+        Err(PuppeteerError::TooManyRouteRedirects(path))
+    }
+
+    fn find(&self, path: &str) -> PuppeteerResult<(Arc<dyn Route>, RouteRequest)> {
+        let (route_path, query) = path.split_once('?').unwrap_or((path, ""));
+        let segments = Router::segments(route_path);
+
+        self.routes
+            .iter()
+            .find_map(|entry| {
+                let params = entry.matches(&segments)?;
+                let query = query
+                    .split('&')
+                    .filter(|pair| !pair.is_empty())
+                    .map(|pair| {
+                        let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+                        (name.to_owned(), value.to_owned())
+                    })
+                    .collect();
+
+                Some((
+                    entry.route.clone(),
+                    RouteRequest {
+                        path: route_path.to_owned(),
+                        params,
+                        query,
+                    },
+                ))
+            })
+            .ok_or_else(|| PuppeteerError::RouteNotFound(path.to_owned()))
+    }
+
+    fn push(&self, path: String) {
+        let mut history = self.lock();
+
+        if !history.entries.is_empty() {
+            let index = history.index + 1;
+            history.entries.truncate(index);
+        }
+
+        history.entries.push(path);
+        history.index = history.entries.len() - 1;
+    }
+
+    fn segments(path: &str) -> Vec<&str> {
+        path.split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, History> {
+        self.history
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl core::fmt::Debug for Router {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Router")
+            .field("routes", &self.routes.len())
+            .field("history", &*self.lock())
+            .finish()
+    }
+}
+
+/// Parse an IPC message sent by the [Router::script] into the navigation it requests
+pub fn route_from_ipc(message: &str) -> Option<ModifyView> {
+    match message.strip_prefix(ROUTE_IPC_PREFIX)? {
+        "back" => Some(ModifyView::Back),
+        "forward" => Some(ModifyView::Forward),
+        command => command
+            .strip_prefix("navigate>")
+            .map(|path| ModifyView::Navigate(path.to_owned())),
+    }
+}
+
+impl VNode {
+    /// Navigate to the `path` when the element is clicked
+    pub fn route(self, path: &str) -> Self {
+        self.attr("data-pp-route", path)
+    }
+}
+
+#[cfg(test)]
+mod router_checks {
+    use super::*;
+
+    struct Page;
+
+    #[async_trait]
+    impl Route for Page {
+        async fn load(
+            &self,
+            request: &RouteRequest,
+            _app_env: &ActiveAppEnv,
+        ) -> PuppeteerResult<ModifyView> {
+            Ok(ModifyView::ReplaceApp(
+                request.path.clone() + &format!("{:?}{:?}", request.params, request.query),
+            ))
+        }
+    }
+
+    struct Admin;
+
+    #[async_trait]
+    impl Route for Admin {
+        async fn guard(&self, request: &RouteRequest, _app_env: &ActiveAppEnv) -> RouteGuard {
+            match request.query("user") {
+                Some("admin") => RouteGuard::Allow,
+                Some(_) => RouteGuard::Deny,
+                None => RouteGuard::Redirect("/login".to_owned()),
+            }
+        }
+
+        async fn load(
+            &self,
+            _request: &RouteRequest,
+            _app_env: &ActiveAppEnv,
+        ) -> PuppeteerResult<ModifyView> {
+            Ok(ModifyView::ReplaceApp("admin".to_owned()))
+        }
+    }
+
+    #[test]
+    fn navigation() {
+        let app_env = ActiveAppEnv::new("router_checks");
+        let router = Router::new()
+            .route("/", Page)
+            .route("/login", Page)
+            .route("/users/:id", Page)
+            .route("/admin", Admin);
+        let navigate = |path: &str| smol::block_on(router.navigate(path, &app_env)).unwrap();
+
+        assert_eq!(ModifyView::ReplaceApp("/{}{}".to_owned()), navigate("/"));
+        assert_eq!(
+            ModifyView::ReplaceApp(r#"/users/7{"id": "7"}{"tab": "posts"}"#.to_owned()),
+            navigate("/users/7?tab=posts")
+        );
+        assert_eq!(ModifyView::Skip, navigate("/admin?user=guest"));
+        assert_eq!(
+            ModifyView::ReplaceApp("/login{}{}".to_owned()),
+            navigate("/admin")
+        );
+        assert_eq!(Some("/login".to_owned()), router.current());
+        assert!(smol::block_on(router.navigate("/missing", &app_env)).is_err());
+
+        smol::block_on(router.back(&app_env)).unwrap();
+        smol::block_on(router.back(&app_env)).unwrap();
+        assert_eq!(Some("/".to_owned()), router.current());
+        assert!(!router.can_go_back());
+        assert_eq!(
+            ModifyView::Skip,
+            smol::block_on(router.back(&app_env)).unwrap()
+        );
+
+        smol::block_on(router.forward(&app_env)).unwrap();
+        assert_eq!(Some("/users/7?tab=posts".to_owned()), router.current());
+
+        navigate("/admin?user=admin");
+        assert!(!router.can_go_forward());
+        assert_eq!(Some("/admin?user=admin".to_owned()), router.current());
+
+        assert_eq!(
+            Some(ModifyView::Navigate("/users/8".to_owned())),
+            route_from_ipc("puppeteer:route>navigate>/users/8")
+        );
+        assert_eq!(
+            Some(ModifyView::Back),
+            route_from_ipc("puppeteer:route>back")
+        );
+        assert_eq!(None, route_from_ipc("puppeteer:route>reload"));
+
+        let macos = Router::script(&AppEnvironment {
+            os: OsType::MacOs,
+            ..AppEnvironment::init()
+        });
+        let linux = Router::script(&AppEnvironment {
+            os: OsType::Linux,
+            ..AppEnvironment::init()
+        });
+        assert!(macos.contains(
+            r#"const keys = {modifier:"metaKey",back:"BracketLeft",forward:"BracketRight"};"#
+        ));
+        assert!(linux.contains(
+            r#"const keys = {modifier:"altKey",back:"ArrowLeft",forward:"ArrowRight"};"#
+        ));
+        assert!(linux.contains("e.target.isContentEditable"));
+    }
+}
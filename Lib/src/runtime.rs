@@ -0,0 +1,447 @@
+/// The JavaScript runtime added to the `<head>` of every [crate::Shell].
+/// [crate::ModifyView] operations call these functions with JSON encoded arguments
+/// instead of building scripts by concatenating the content so that content containing
+/// quotes, backticks or `${...}` can never break out of the script.
+pub const PUPPETEER_RUNTIME_SCRIPT: &str = r#"
+<script>
+window.puppeteer = (function () {
+    const byId = (id) => document.getElementById(id);
+
+    // The nonce of the Content-Security-Policy given to injected scripts and styles
+    const nonce = document.currentScript === null ? "" : document.currentScript.nonce;
+
+    const named = (tag, attribute, name) =>
+        Array.from(document.querySelectorAll(tag + "[" + attribute + "]")).find((node) => node.getAttribute(attribute) === name);
+
+    const bindings = (field) =>
+        Array.from(document.querySelectorAll("[data-pp-bind]")).filter((node) => node.dataset.ppBind === field);
+
+    const isFormField = (node) =>
+        node instanceof HTMLInputElement || node instanceof HTMLTextAreaElement || node instanceof HTMLSelectElement;
+
+    // The same value as `DEFAULT_BINDING_DEBOUNCE_MS`
+    const bindingDebounce = 150;
+    const debounced = new WeakMap();
+
+    const sendBinding = (e) => {
+        const node = e.target;
+
+        if (!(node instanceof Element) || node.dataset.ppBind === undefined || !isFormField(node)) {
+            return;
+        }
+
+        const value = node.type === "checkbox" ? String(node.checked) : node.value;
+        const delay = Number(node.dataset.ppDebounce || bindingDebounce);
+
+        clearTimeout(debounced.get(node));
+        debounced.set(node, setTimeout(() => {
+            window.ipc.postMessage("puppeteer:bind>" + node.dataset.ppBind + ">" + value);
+        }, delay));
+    };
+
+    document.addEventListener("input", sendBinding);
+    document.addEventListener("change", sendBinding);
+
+    const modalFocusable = "button, [href], input, select, textarea, [tabindex]:not([tabindex='-1'])";
+
+    // Clicking the action or the close button of a toast removes it
+    document.addEventListener("click", (e) => {
+        const button = e.target instanceof Element ? e.target.closest(".pp-toast button") : null;
+
+        if (button !== null) {
+            button.closest(".pp-toast").remove();
+        }
+    });
+
+    // Virtual lists request the visible rows, with the overscan, when they are
+    // added to the document, scrolled or resized
+    const requestRows = (list) => {
+        const rowHeight = Number(list.dataset.ppRowHeight) || 1;
+        const overscan = Number(list.dataset.ppOverscan) || 0;
+        const start = Math.max(0, Math.floor(list.scrollTop / rowHeight) - overscan);
+        const end = Math.ceil((list.scrollTop + list.clientHeight) / rowHeight) + overscan;
+        const range = start + ">" + end;
+
+        if (list.dataset.ppRange !== range) {
+            list.dataset.ppRange = range;
+            window.ipc.postMessage("puppeteer:vlist>" + list.id + ">" + range);
+        }
+    };
+
+    document.addEventListener("scroll", (e) => {
+        if (e.target instanceof Element && e.target.matches("[data-pp-vlist]")) {
+            requestRows(e.target);
+        }
+    }, true);
+    window.addEventListener("resize", () => document.querySelectorAll("[data-pp-vlist]").forEach(requestRows));
+    new MutationObserver(() => {
+        document.querySelectorAll("[data-pp-vlist]:not([data-pp-range])").forEach(requestRows);
+    }).observe(document.documentElement, { childList: true, subtree: true });
+
+    // `VNode::on` and `VNode::on_value` add `data-pp-on-{event}` and `data-pp-value-on-{event}`
+    // attributes instead of inline handlers, which a Content-Security-Policy blocks.
+    // One listener per event is added to the document the first time the event is used
+    const delegatedEvents = new Set();
+    const delegatedAttribute = /^data-pp-(?:value-)?on-(.+)$/;
+
+    // Attributes can hold app messages and the messages of routes, components, bindings and
+    // modals. Other `puppeteer:` messages like clipboard requests, shortcuts and CSP reports
+    // are dropped so that injected HTML cannot send them
+    const delegatedPrefixes = ["puppeteer:route>", "puppeteer:component>", "puppeteer:bind>", "puppeteer:modal>"];
+    const postDelegated = (message) => {
+        if (!message.startsWith("puppeteer:") || delegatedPrefixes.some((prefix) => message.startsWith(prefix))) {
+            window.ipc.postMessage(message);
+        }
+    };
+
+    const dispatchDelegated = (e) => {
+        const message = "data-pp-on-" + e.type;
+        const prefix = "data-pp-value-on-" + e.type;
+        const selector = "[" + message + "], [" + prefix + "]";
+
+        if (!(e.target instanceof Element)) {
+            return;
+        }
+
+        const node = e.bubbles ? e.target.closest(selector) : e.target.matches(selector) ? e.target : null;
+
+        if (node === null) {
+            return;
+        }
+
+        if (node.hasAttribute(message)) {
+            postDelegated(node.getAttribute(message));
+        }
+
+        if (node.hasAttribute(prefix)) {
+            postDelegated(node.getAttribute(prefix) + node.value);
+        }
+    };
+
+    const delegate = (node) => {
+        if (!(node instanceof Element)) {
+            return;
+        }
+
+        [node, ...node.querySelectorAll("*")].forEach((element) => {
+            for (const attribute of element.attributes) {
+                const event = delegatedAttribute.exec(attribute.name);
+
+                if (event !== null && !delegatedEvents.has(event[1])) {
+                    delegatedEvents.add(event[1]);
+                    document.addEventListener(event[1], dispatchDelegated, true);
+                }
+            }
+        });
+    };
+
+    new MutationObserver((mutations) => {
+        mutations.forEach((mutation) => {
+            if (mutation.type === "attributes") {
+                delegate(mutation.target);
+            } else {
+                mutation.addedNodes.forEach(delegate);
+            }
+        });
+    }).observe(document.documentElement, { childList: true, subtree: true, attributes: true });
+
+    // Violations of the Content-Security-Policy are sent to `Puppeteer::error_handler()`
+    // with the nonce, which HTML injected into the document cannot read
+    document.addEventListener("securitypolicyviolation", (e) => {
+        if (nonce !== "") {
+            window.ipc.postMessage("puppeteer:csp>" + nonce + ">" + e.effectiveDirective + ">" + e.blockedURI);
+        }
+    });
+
+    const nodes = (target) => {
+        if (target.id !== undefined) {
+            const node = byId(target.id);
+
+            return node === null ? [] : [node];
+        }
+
+        return Array.from(document.querySelectorAll(target.selector));
+    };
+
+    return {
+        replaceApp(content) {
+            byId("puppeteer_app").innerHTML = content;
+        },
+        replaceNode(id, content) {
+            const node = byId(id);
+
+            if (node !== null) {
+                node.innerHTML = content;
+            }
+        },
+        textContent(id) {
+            const node = byId(id);
+
+            return node === null ? null : node.textContent;
+        },
+        inputValue(id) {
+            const node = byId(id);
+
+            return node === null ? null : node.value;
+        },
+        insertAdjacent(target, position, content) {
+            nodes(target).forEach((node) => node.insertAdjacentHTML(position, content));
+        },
+        remove(target) {
+            nodes(target).forEach((node) => node.remove());
+        },
+        setAttribute(target, name, value) {
+            nodes(target).forEach((node) => node.setAttribute(name, value));
+        },
+        removeAttribute(target, name) {
+            nodes(target).forEach((node) => node.removeAttribute(name));
+        },
+        addClass(target, name) {
+            nodes(target).forEach((node) => node.classList.add(name));
+        },
+        removeClass(target, name) {
+            nodes(target).forEach((node) => node.classList.remove(name));
+        },
+        toggleClass(target, name) {
+            nodes(target).forEach((node) => node.classList.toggle(name));
+        },
+        setStyle(target, property, value) {
+            nodes(target).forEach((node) => node.style.setProperty(property, value));
+        },
+        setValue(target, value) {
+            nodes(target).forEach((node) => {
+                node.value = value;
+            });
+        },
+        setText(target, text) {
+            nodes(target).forEach((node) => {
+                node.textContent = text;
+            });
+        },
+        setBinding(field, value) {
+            bindings(field).forEach((node) => {
+                if (!isFormField(node)) {
+                    node.textContent = value;
+                } else if (node.type === "checkbox") {
+                    node.checked = value === "true";
+                } else if (node.value !== value) {
+                    // Only changed values are set so the cursor of the focused field is kept
+                    node.value = value;
+                }
+            });
+        },
+        setTheme(theme) {
+            document.documentElement.dataset.ppTheme = theme;
+        },
+        setTitle(title) {
+            document.title = title;
+        },
+        injectStyle(name, css) {
+            let style = named("style", "data-pp-style", name);
+
+            if (style === undefined) {
+                style = document.createElement("style");
+                style.setAttribute("data-pp-style", name);
+                style.nonce = nonce;
+                document.head.appendChild(style);
+            }
+
+            if (style.textContent !== css) {
+                style.textContent = css;
+            }
+        },
+        removeStyle(name) {
+            const style = named("style", "data-pp-style", name);
+
+            if (style !== undefined) {
+                style.remove();
+            }
+        },
+        injectScript(name, source, content, module, head) {
+            const previous = named("script", "data-pp-script", name);
+
+            if (previous !== undefined) {
+                if (previous.getAttribute("src") === source && previous.textContent === content && (previous.type === "module") === module) {
+                    return;
+                }
+
+                previous.remove();
+            }
+
+            // Scripts inserted using `innerHTML` never run so the element is created
+            const script = document.createElement("script");
+            script.setAttribute("data-pp-script", name);
+            script.nonce = nonce;
+
+            if (module) {
+                script.type = "module";
+            }
+
+            if (source === null) {
+                script.textContent = content;
+            } else {
+                script.src = source;
+            }
+
+            (head ? document.head : document.body).appendChild(script);
+        },
+        removeScript(name) {
+            const script = named("script", "data-pp-script", name);
+
+            if (script !== undefined) {
+                script.remove();
+            }
+        },
+        setListRows(id, total, start, rows) {
+            const list = byId(id);
+            const container = byId(id + "-rows");
+
+            if (list === null || container === null) {
+                return;
+            }
+
+            const rowHeight = Number(list.dataset.ppRowHeight) || 1;
+
+            list.firstElementChild.style.height = total * rowHeight + "px";
+            container.style.transform = "translateY(" + start * rowHeight + "px)";
+            container.innerHTML = rows;
+        },
+        showToast(content, timeout) {
+            const container = byId("pp-toasts");
+
+            if (container === null) {
+                return;
+            }
+
+            container.insertAdjacentHTML("beforeend", content);
+
+            const toast = container.lastElementChild;
+
+            if (timeout !== null) {
+                setTimeout(() => toast.remove(), timeout);
+            }
+        },
+        showModal(id, content) {
+            const previous = document.activeElement;
+            const overlay = document.createElement("div");
+            const dialog = document.createElement("div");
+
+            overlay.className = "pp-modal-overlay";
+            dialog.className = "pp-modal";
+            dialog.setAttribute("role", "dialog");
+            dialog.setAttribute("aria-modal", "true");
+            dialog.tabIndex = -1;
+            dialog.innerHTML = content;
+            overlay.appendChild(dialog);
+            document.body.appendChild(overlay);
+
+            const close = (action) => {
+                const input = dialog.querySelector("[data-pp-modal-input]");
+                const value = input === null ? "" : input.value;
+
+                overlay.remove();
+
+                if (previous instanceof HTMLElement) {
+                    previous.focus();
+                }
+
+                window.ipc.postMessage("puppeteer:modal>" + id + ">" + action + ">" + value);
+            };
+
+            overlay.addEventListener("click", (e) => {
+                const action = e.target instanceof Element ? e.target.closest("[data-pp-modal-action]") : null;
+
+                if (action !== null && dialog.contains(action)) {
+                    close(action.dataset.ppModalAction);
+                }
+            });
+            overlay.addEventListener("keydown", (e) => {
+                if (e.key === "Escape") {
+                    e.preventDefault();
+                    close("cancel");
+                } else if (e.key === "Enter" && e.target instanceof Element && e.target.matches("[data-pp-modal-input]")) {
+                    e.preventDefault();
+                    close("ok");
+                } else if (e.key === "Tab") {
+                    const focusable = Array.from(dialog.querySelectorAll(modalFocusable));
+                    const first = focusable[0];
+                    const last = focusable[focusable.length - 1];
+
+                    if (focusable.length === 0) {
+                        e.preventDefault();
+                    } else if (e.shiftKey && (document.activeElement === first || document.activeElement === dialog)) {
+                        e.preventDefault();
+                        last.focus();
+                    } else if (!e.shiftKey && document.activeElement === last) {
+                        e.preventDefault();
+                        first.focus();
+                    }
+                }
+            });
+
+            (dialog.querySelector("[data-pp-modal-input]") || dialog.querySelector(modalFocusable) || dialog).focus();
+        },
+        patch(id, patches) {
+            const root = byId(id);
+
+            if (root === null) {
+                return;
+            }
+
+            const at = (path) => path.reduce((node, index) => node.childNodes[index], root.firstChild);
+            const parse = (html) => {
+                const template = document.createElement("template");
+                template.innerHTML = html;
+
+                return template.content.firstChild || document.createTextNode("");
+            };
+
+            patches.forEach((patch) => {
+                switch (patch.op) {
+                    case "replace":
+                        at(patch.path).replaceWith(parse(patch.html));
+                        break;
+                    case "text":
+                        at(patch.path).nodeValue = patch.text;
+                        break;
+                    case "attr": {
+                        const node = at(patch.path);
+                        node.setAttribute(patch.name, patch.value);
+
+                        // The attributes are only the initial state of form fields
+                        if (patch.name === "value") {
+                            node.value = patch.value;
+                        } else if (patch.name === "checked") {
+                            node.checked = true;
+                        }
+                        break;
+                    }
+                    case "rmattr": {
+                        const node = at(patch.path);
+                        node.removeAttribute(patch.name);
+
+                        if (patch.name === "checked") {
+                            node.checked = false;
+                        }
+                        break;
+                    }
+                    case "insert": {
+                        const parent = at(patch.path);
+                        parent.insertBefore(parse(patch.html), parent.childNodes[patch.index] || null);
+                        break;
+                    }
+                    case "remove":
+                        at(patch.path).remove();
+                        break;
+                    case "move": {
+                        const parent = at(patch.path);
+                        parent.insertBefore(parent.childNodes[patch.from], parent.childNodes[patch.to]);
+                        break;
+                    }
+                }
+            });
+        },
+    };
+})();
+</script>
+"#;
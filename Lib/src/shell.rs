@@ -1,41 +1,201 @@
-use crate::{ActiveAppEnv, StaticAssetProperties, StaticCowStr, StaticStr, UiPaint};
-use file_format::FileFormat;
-use std::borrow::Cow;
+use crate::{
+    escape_html, minify_css, minify_script_elements, ActiveAppEnv, ContentSecurityPolicy,
+    FontFamily, FontVariant, HexColor, PuppeteerResult, Script, ScriptPlacement, StaticAsset,
+    StaticAssetProperties, StaticCowStr, StaticStr, TextDirection, UiPaint,
+    PUPPETEER_RUNTIME_SCRIPT, TOAST_CONTAINER_ELEMENT,
+};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashSet},
+};
 use tao::window::Theme as WryTheme;
 
 /// The HTML element where all the app body will be injected
 pub const PUPPETEER_APP_ELEMENT: &str = r#"<div id="puppeteer_app"></div>"#;
 
-/// This is the color palette of the app
+/// This is the color palette of the app.
+/// Every color is added to the [Shell] as a CSS custom property like `--pp-primary`
+/// so that all the styles, including the built-in ones, can reference them using `var()`
 #[derive(Debug, PartialEq, Eq, Clone, PartialOrd, Ord)]
 pub struct ColorPalette {
     /// The dominant color
-    pub primary: StaticStr, //FIXME switch to HexColor
+    pub primary: HexColor,
     /// The color to contrast the dominant color
-    pub secondary: StaticStr, //FIXME switch to HexColor
+    pub secondary: HexColor,
     /// The accent color
-    pub tertiary: StaticStr, //FIXME switch to HexColor
+    pub tertiary: HexColor,
+    /// The background of the app
+    pub background: HexColor,
+    /// The background of raised elements like modals and menus
+    pub surface: HexColor,
+    /// The color of the text
+    pub text: HexColor,
+    /// The color of errors
+    pub error: HexColor,
+    /// The color of warnings
+    pub warning: HexColor,
+    /// The color of successful operations
+    pub success: HexColor,
+    /// The color of borders and separators
+    pub border: HexColor,
+}
+
+impl ColorPalette {
+    /// The name of each color of the palette with the color.
+    /// The CSS custom property of a color is `--pp-{name}`
+    pub fn colors(&self) -> [(&'static str, HexColor); 10] {
+        [
+            ("primary", self.primary),
+            ("secondary", self.secondary),
+            ("tertiary", self.tertiary),
+            ("background", self.background),
+            ("surface", self.surface),
+            ("text", self.text),
+            ("error", self.error),
+            ("warning", self.warning),
+            ("success", self.success),
+            ("border", self.border),
+        ]
+    }
+
+    /// The declarations of the CSS custom properties like `--pp-primary: #FFFFFF;`.
+    /// Each color also has a `--pp-on-{name}` property with the [HexColor::readable_text] of the color
+    pub fn css_variables(&self) -> String {
+        let colors = self.colors();
+
+        colors
+            .iter()
+            .map(|(name, color)| String::from("--pp-") + name + ": " + &color.to_hex() + ";")
+            .chain(colors.iter().map(|(name, color)| {
+                String::from("--pp-on-") + name + ": " + &color.readable_text().to_hex() + ";"
+            }))
+            .collect()
+    }
+
+    /// Generate a [Theme::Light] palette from a brand color used as the primary color.
+    /// The other colors are tones of the same hue
+    pub fn from_seed(seed: HexColor) -> Self {
+        let (_, saturation, _) = seed.to_hsl();
+
+        ColorPalette {
+            primary: seed,
+            secondary: seed.readable_text(),
+            tertiary: seed.tone(saturation * 0.6, 0.90),
+            background: seed.tone(saturation * 0.2, 0.98),
+            surface: seed.tone(saturation * 0.25, 0.95),
+            text: seed.tone(saturation * 0.3, 0.10),
+            border: seed.tone(saturation * 0.2, 0.80),
+            ..ColorPalette::light()
+        }
+    }
+
+    /// Generate a [Theme::Dark] palette from a brand color used as the primary color.
+    /// The other colors are tones of the same hue
+    pub fn dark_from_seed(seed: HexColor) -> Self {
+        let (_, saturation, _) = seed.to_hsl();
+
+        ColorPalette {
+            primary: seed,
+            secondary: seed.readable_text(),
+            tertiary: seed.tone(saturation * 0.5, 0.25),
+            background: seed.tone(saturation * 0.2, 0.07),
+            surface: seed.tone(saturation * 0.25, 0.12),
+            text: seed.tone(saturation * 0.2, 0.95),
+            border: seed.tone(saturation * 0.2, 0.30),
+            ..ColorPalette::dark()
+        }
+    }
+}
+
+impl ColorPalette {
+    /// The default palette of the [Theme::Light] theme
+    pub fn light() -> Self {
+        ColorPalette::default()
+    }
+
+    /// The default palette of the [Theme::Dark] theme
+    pub fn dark() -> Self {
+        ColorPalette {
+            primary: HexColor::rgb(0x1E, 0x1E, 0x1E),
+            secondary: HexColor::rgb(0xFF, 0xFF, 0xFF),
+            tertiary: HexColor::rgb(0x33, 0x33, 0x33),
+            background: HexColor::rgb(0x12, 0x12, 0x12),
+            surface: HexColor::rgb(0x1E, 0x1E, 0x1E),
+            text: HexColor::rgb(0xFF, 0xFF, 0xFF),
+            error: HexColor::rgb(0xEF, 0x53, 0x50),
+            warning: HexColor::rgb(0xFF, 0xA7, 0x26),
+            success: HexColor::rgb(0x66, 0xBB, 0x6A),
+            border: HexColor::rgb(0x44, 0x44, 0x44),
+        }
+    }
 }
 
 impl Default for ColorPalette {
     fn default() -> Self {
         ColorPalette {
-            primary: "#FFFFFF",
-            secondary: "#000000",
-            tertiary: "#E6E6E6",
+            primary: HexColor::rgb(0xFF, 0xFF, 0xFF),
+            secondary: HexColor::rgb(0x00, 0x00, 0x00),
+            tertiary: HexColor::rgb(0xE6, 0xE6, 0xE6),
+            background: HexColor::rgb(0xFF, 0xFF, 0xFF),
+            surface: HexColor::rgb(0xF5, 0xF5, 0xF5),
+            text: HexColor::rgb(0x00, 0x00, 0x00),
+            error: HexColor::rgb(0xD3, 0x2F, 0x2F),
+            warning: HexColor::rgb(0xED, 0x6C, 0x02),
+            success: HexColor::rgb(0x2E, 0x7D, 0x32),
+            border: HexColor::rgb(0xCC, 0xCC, 0xCC),
         }
     }
 }
 
 /// The [Shell] of the app contains all the imports
 /// like fonts, styles and scripts
-#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Shell {
     head_links: Vec<StaticCowStr>,
     styles: Vec<StaticCowStr>,
+    style_names: BTreeMap<usize, String>,
     scripts: Vec<StaticCowStr>,
     fonts: Vec<StaticCowStr>,
+    font_preloads: Vec<String>,
     palette: ColorPalette,
+    dark_palette: ColorPalette,
+    theme: Theme,
+    system_theme: Theme,
+    language: Option<String>,
+    direction: TextDirection,
+    content_security_policy: Option<ContentSecurityPolicy>,
+    title: Option<String>,
+    meta: Vec<String>,
+    favicon: Option<String>,
+    body_classes: Vec<String>,
+    head_scripts: Vec<StaticCowStr>,
+    minify: bool,
+}
+
+impl Default for Shell {
+    fn default() -> Self {
+        Shell {
+            head_links: Vec::default(),
+            styles: Vec::default(),
+            style_names: BTreeMap::default(),
+            scripts: Vec::default(),
+            fonts: Vec::default(),
+            font_preloads: Vec::default(),
+            palette: ColorPalette::light(),
+            dark_palette: ColorPalette::dark(),
+            theme: Theme::default(),
+            system_theme: Theme::default(),
+            language: Option::default(),
+            direction: TextDirection::default(),
+            content_security_policy: Option::default(),
+            title: Option::default(),
+            meta: Vec::default(),
+            favicon: Option::default(),
+            body_classes: Vec::default(),
+            head_scripts: Vec::default(),
+            minify: false,
+        }
+    }
 }
 
 impl Shell {
@@ -52,7 +212,17 @@ impl Shell {
         self
     }
 
+    /// Add an element like a `<link>` or `<meta>` built using [crate::Html] into the `<head>`
+    pub fn add_head_element(mut self, element: impl UiPaint) -> Self {
+        self.head_links
+            .push(Cow::Owned(element.to_html().to_string()));
+
+        self
+    }
+
     /// Add styles into the `<style></style>` element.
+    /// A style with the same content as a style already added is only added once,
+    /// at the position of the last copy so that it still wins the cascade
     pub fn add_style(mut self, style: StaticStr) -> Self {
         self.styles.push(Cow::Borrowed(style));
 
@@ -76,7 +246,31 @@ impl Shell {
         self
     }
 
-    /// Add the scripts in the `<body></body>` field
+    /// Add a style in its own `<style>` element named `name`, which can be replaced
+    /// or removed at runtime using [crate::ModifyView::InjectStyle] and
+    /// [crate::ModifyView::RemoveStyle]
+    pub fn add_named_style(mut self, name: &str, style: StaticCowStr) -> Self {
+        self.style_names.insert(self.styles.len(), name.to_owned());
+        self.styles.push(style);
+
+        self
+    }
+
+    /// Name the last style added with the same content as `css`, see [Shell::add_named_style].
+    /// Nothing happens if no style has the same content
+    pub fn name_style(mut self, name: &str, css: &str) -> Self {
+        if let Some(index) = self
+            .styles
+            .iter()
+            .rposition(|style| style.trim() == css.trim())
+        {
+            self.style_names.insert(index, name.to_owned());
+        }
+
+        self
+    }
+
+    /// Add the scripts at the end of the `<body></body>` field
     pub fn add_script(mut self, script: StaticCowStr) -> Self {
         self.scripts.push(script);
 
@@ -100,6 +294,150 @@ impl Shell {
         self
     }
 
+    /// Add a [Script] to the `<head>` or the end of the `<body>` depending on its [ScriptPlacement]
+    pub fn add_script_element(mut self, script: Script) -> Self {
+        let element = Cow::Owned(script.to_html().to_string());
+
+        match script.placement() {
+            ScriptPlacement::Head => self.head_scripts.push(element),
+            ScriptPlacement::BodyEnd => self.scripts.push(element),
+        }
+
+        self
+    }
+
+    /// Set the `<title>` of the document. By default it is the title of the window
+    /// and [crate::ModifyView::SetTitle] changes both
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title.replace(title.to_owned());
+
+        self
+    }
+
+    /// Add a `<meta name="{name}" content="{content}">` tag
+    pub fn add_meta(mut self, name: &str, content: &str) -> Self {
+        self.meta.push(
+            String::from(r#"<meta name=""#)
+                + &escape_html(name)
+                + r#"" content=""#
+                + &escape_html(content)
+                + r#"">"#,
+        );
+
+        self
+    }
+
+    /// Use the image asset, like a PNG or SVG file, as the icon of the document
+    pub fn with_favicon(mut self, asset: &impl StaticAssetProperties) -> Self {
+        self.favicon.replace(
+            String::from(r#"<link rel="icon" type=""#)
+                + &escape_html(asset.format().media_type())
+                + r#"" href=""#
+                + &asset.base64()
+                + r#"">"#,
+        );
+
+        self
+    }
+
+    /// Add a class to the `<body>` element
+    pub fn add_body_class(mut self, class: &str) -> Self {
+        self.body_classes.push(class.to_owned());
+
+        self
+    }
+
+    /// Set the [ColorPalette] of the [Theme::Light] theme, added as CSS custom properties
+    pub fn with_palette(mut self, palette: ColorPalette) -> Self {
+        self.palette = palette;
+
+        self
+    }
+
+    /// Set the [ColorPalette] of the [Theme::Dark] theme. Defaults to [ColorPalette::dark]
+    pub fn with_dark_palette(mut self, palette: ColorPalette) -> Self {
+        self.dark_palette = palette;
+
+        self
+    }
+
+    /// Set the theme used when the app starts. Defaults to [Theme::System]
+    /// which follows the theme of the operating system
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+
+        self
+    }
+
+    /// Get the [ColorPalette] of the [Theme::Light] theme
+    pub fn palette(&self) -> &ColorPalette {
+        &self.palette
+    }
+
+    /// Get the [ColorPalette] of the [Theme::Dark] theme
+    pub fn dark_palette(&self) -> &ColorPalette {
+        &self.dark_palette
+    }
+
+    /// Get the theme used when the app starts
+    pub fn theme(&self) -> Theme {
+        self.theme
+    }
+
+    /// Set the theme of the operating system, used when the theme is [Theme::System]
+    pub(crate) fn with_system_theme(mut self, system_theme: Theme) -> Self {
+        self.system_theme = system_theme;
+
+        self
+    }
+
+    /// Set the `lang` and `dir` attributes of the `<html>` element.
+    /// By default they are set from the [crate::Localization] of the app
+    pub fn with_language(mut self, language: &str, direction: TextDirection) -> Self {
+        self.language.replace(language.to_owned());
+        self.direction = direction;
+
+        self
+    }
+
+    /// Get the language set using [Shell::with_language]
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    /// Get the direction of the text
+    pub fn direction(&self) -> TextDirection {
+        self.direction
+    }
+
+    /// Add a `<meta>` Content-Security-Policy. The sources of the `head_links`
+    /// are allowed and a nonce is added to every script of the [Shell]
+    pub fn with_content_security_policy(mut self, policy: ContentSecurityPolicy) -> Self {
+        self.content_security_policy.replace(policy);
+
+        self
+    }
+
+    /// Get the Content-Security-Policy set using [Shell::with_content_security_policy]
+    pub fn content_security_policy(&self) -> Option<&ContentSecurityPolicy> {
+        self.content_security_policy.as_ref()
+    }
+
+    /// Get the title set using [Shell::with_title]
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Get the classes of the `<body>` element
+    pub fn body_classes(&self) -> &[String] {
+        self.body_classes.as_slice()
+    }
+
+    /// Get the scripts added to the `<head>`
+    pub fn head_scripts(&self) -> &[StaticCowStr] {
+        self.head_scripts.as_slice()
+    }
+
     /// Get the head_links
     pub fn head_links(&self) -> &[StaticCowStr] {
         self.head_links.as_slice()
@@ -115,87 +453,329 @@ impl Shell {
         self.scripts.as_slice()
     }
 
-    /// Add user specified fonts
-    pub fn add_fonts(mut self, app_env: &ActiveAppEnv) -> Self {
-        app_env.fonts.iter().for_each(|font| {
-            let file_format_detected = font.format();
+    /// Minify the styles and the content of the scripts when the document is built,
+    /// see [crate::minify_css] and [crate::minify_js]
+    pub fn minify(mut self) -> Self {
+        self.minify = true;
+
+        self
+    }
+
+    /// Whether the styles and scripts are minified
+    pub fn is_minified(&self) -> bool {
+        self.minify
+    }
+
+    /// The size of the document and of its parts after removing duplicates and minifying
+    pub fn size_report(&self) -> ShellSizeReport {
+        self.to_html_with_size_report().1
+    }
 
-            if file_format_detected != FileFormat::WebOpenFontFormat2 {
-                panic!(
-                    "Invalid font type `{:?}` for file `{}`",
-                    font.format(),
-                    font.name
-                );
+    /// The head links, styles, fonts and scripts without the entries already added
+    /// and minified if [Shell::minify] is set
+    fn bundle(&self) -> ShellBundle {
+        let mut duplicates = 0;
+        let mut seen_scripts = HashSet::new();
+
+        let mut head_scripts = self.script_entries(
+            &self.head_scripts,
+            unique(&self.head_scripts, &mut seen_scripts, &mut duplicates),
+        );
+        let mut scripts = self.script_entries(
+            &self.scripts,
+            unique(&self.scripts, &mut seen_scripts, &mut duplicates),
+        );
+        let mut fonts = concat(
+            &self.fonts,
+            unique(&self.fonts, &mut HashSet::new(), &mut duplicates),
+        );
+        let head_links = self.script_entries(
+            &self.head_links,
+            unique(&self.head_links, &mut HashSet::new(), &mut duplicates),
+        );
+        let font_preloads = self.script_entries(
+            &self.font_preloads,
+            unique(&self.font_preloads, &mut HashSet::new(), &mut duplicates),
+        );
+        let mut runtime = self.script_entries(&[PUPPETEER_RUNTIME_SCRIPT], vec![0]);
+
+        if self.minify {
+            runtime = minify_script_elements(&runtime);
+            head_scripts = minify_script_elements(&head_scripts);
+            scripts = minify_script_elements(&scripts);
+            fonts = minify_css(&fonts);
+        }
+
+        // Named styles have their own element so that they can be replaced at runtime
+        let mut styles = String::default();
+        let mut unnamed = String::default();
+
+        for index in unique_last(&self.styles, &mut duplicates) {
+            match self.style_names.get(&index) {
+                Some(name) => {
+                    styles += &self.style_element(None, &core::mem::take(&mut unnamed));
+                    styles += &self.style_element(Some(name), &self.styles[index]);
+                }
+                None => unnamed += &self.styles[index],
             }
+        }
 
-            tracing::info!("LOADED FONT: {:?}", &font.name());
+        styles += &self.style_element(None, &unnamed);
+
+        ShellBundle {
+            head_links,
+            styles,
+            fonts,
+            font_preloads,
+            runtime,
+            head_scripts,
+            scripts,
+            duplicates,
+        }
+    }
 
-            let injector = Cow::Borrowed("var dataUri = \"")
-                + font.base64()
-                + "\";"
-                + Cow::Borrowed(
-                    r#"
-                    var fontFace = new FontFace(""#,
-                )
-                + font.name()
-                + Cow::Borrowed(
-                    r#"", `url(${dataUri})`, {
-                        style: "normal",
-                        weight: "normal",
-                        stretch: "condensed",
-                      });
-                      document.fonts.add(fontFace);
-                    "#,
-                ); //FIXME Add styles for fonts here*/
-            self.fonts.push(Cow::Owned(injector.to_string()));
-        });
+    /// The entries at `indices` with the nonce of the [ContentSecurityPolicy] added to
+    /// each `<script>` element as it is emitted
+    fn script_entries(&self, entries: &[impl AsRef<str>], indices: Vec<usize>) -> String {
+        match self.content_security_policy.as_ref() {
+            Some(policy) => indices
+                .into_iter()
+                .map(|index| policy.add_nonce(entries[index].as_ref()))
+                .collect(),
+            None => concat(entries, indices),
+        }
+    }
 
-        self
+    /// A `<style>` element named `name`. Unnamed elements without CSS are skipped
+    fn style_element(&self, name: Option<&String>, css: &str) -> String {
+        let opening = match name {
+            Some(name) => String::from(r#"<style data-pp-style=""#) + &escape_html(name) + r#"">"#,
+            None if css.is_empty() => return String::default(),
+            None => String::from("<style>"),
+        };
+
+        let css = if self.minify {
+            minify_css(css)
+        } else {
+            css.to_owned()
+        };
+
+        opening + &css + "</style>"
     }
-}
 
-impl UiPaint for Shell {
-    fn to_html(&self) -> Cow<str> {
-        let head_links = self.head_links.iter().cloned().collect::<String>();
+    /// Add the `@font-face` rules of the family and the script preloading its variants.
+    /// Returns an error if a font is not a WOFF2, WOFF, TTF or OTF file or a variant is invalid
+    pub fn add_font_family(mut self, family: &FontFamily) -> PuppeteerResult<Self> {
+        self.fonts.push(Cow::Owned(family.css()?));
+        self.font_preloads.push(family.preload_script()?);
+
+        tracing::info!("LOADED FONT FAMILY: {:?}", family.name());
 
-        let styles = self.styles.iter().cloned().collect::<String>();
+        Ok(self)
+    }
 
-        let scripts = self.scripts.iter().cloned().collect::<String>();
+    /// Add each font of [ActiveAppEnv::fonts] as a family named after the asset.
+    /// The weight, style and characters are read from the font tables, falling back to
+    /// the weight `400` and the [crate::FontStyle::Normal] style if they cannot be read
+    pub fn add_fonts(self, app_env: &ActiveAppEnv) -> PuppeteerResult<Self> {
+        app_env.fonts.iter().try_fold(self, |shell, font| {
+            let variant = FontVariant::new(*font);
+            let variant = match variant.metadata() {
+                Ok(metadata) => variant.with_metadata(&metadata),
+                Err(error) => {
+                    tracing::warn!("COULD NOT READ THE TABLES OF FONT `{}`: {error}", font.name);
+
+                    variant
+                }
+            };
+
+            shell.add_font_family(&FontFamily::new(font.name).variant(variant))
+        })
+    }
 
-        let fonts = self
-            .fonts
+    /// Add the fonts grouped into families named after the family in their tables,
+    /// see [FontFamily::detect]
+    pub fn add_detected_fonts(self, fonts: &[StaticAsset]) -> PuppeteerResult<Self> {
+        FontFamily::detect(fonts.iter().copied().map(FontVariant::new))?
             .iter()
-            .map(|font| font.to_string())
-            .collect::<String>();
+            .try_fold(self, Shell::add_font_family)
+    }
 
-        Cow::Borrowed("<!DOCTYPE html>")
+    /// Build the document and its [ShellSizeReport] without building the document twice
+    pub fn to_html_with_size_report(&self) -> (String, ShellSizeReport) {
+        let ShellBundle {
+            head_links,
+            styles,
+            fonts,
+            font_preloads,
+            runtime,
+            head_scripts,
+            scripts,
+            duplicates,
+        } = self.bundle();
+
+        let mut size_report = ShellSizeReport {
+            document: 0,
+            styles: styles.len(),
+            scripts: runtime.len() + head_scripts.len() + scripts.len(),
+            fonts: fonts.len() + font_preloads.len(),
+            duplicates,
+        };
+
+        let html = String::from("<html")
+            + &self
+                .language
+                .as_ref()
+                .map(|language| String::from(r#" lang=""#) + &escape_html(language) + "\"")
+                .unwrap_or_default()
+            + r#" dir=""#
+            + self.direction.as_str()
+            + r#"" data-pp-theme=""#
+            + self.theme.resolve(self.system_theme).as_str()
+            + r#"">"#;
+
+        let body = if self.body_classes.is_empty() {
+            String::from("<body>")
+        } else {
+            String::from(r#"<body class=""#) + &escape_html(&self.body_classes.join(" ")) + r#"">"#
+        };
+
+        let policy = self
+            .content_security_policy
+            .as_ref()
+            .map(|policy| {
+                self.head_links
+                    .iter()
+                    .chain(self.head_scripts.iter())
+                    .chain(self.scripts.iter())
+                    .fold(policy.clone(), |policy, element| {
+                        policy.allow_head_link(element)
+                    })
+                    .to_html()
+                    .to_string()
+            })
+            .unwrap_or_default();
+
+        let document = Cow::Borrowed("<!DOCTYPE html>")
+            + Cow::Owned(html)
             + "<head>"
             + r#"<meta charset="UTF-8">"#
             + r#"<meta name="viewport" content="width=device-width, initial-scale=1.0">"#
+            + Cow::Owned(policy)
+            + Cow::Owned(self.meta.concat())
+            + "<title>"
+            + Cow::Owned(escape_html(self.title.as_deref().unwrap_or_default()))
+            + "</title>"
+            + Cow::Owned(self.favicon.clone().unwrap_or_default())
+            + Cow::Owned(runtime)
+            + Cow::Owned(head_scripts)
             + Cow::Owned(head_links)
             + "<style>"
-            + Cow::Owned(styles)
+            + ":root {"
+            + Cow::Owned(self.palette.css_variables())
+            + "}"
+            + r#":root[data-pp-theme="dark"] {"#
+            + Cow::Owned(self.dark_palette.css_variables())
+            + "}"
+            + Cow::Owned(fonts)
             + "</style>"
+            + Cow::Owned(styles)
+            + Cow::Owned(font_preloads)
             + "</head>"
-            + "<body>"
-            + "<script>"
-            + Cow::Owned(fonts)
-            + "</script>"
+            + Cow::Owned(body)
             + PUPPETEER_APP_ELEMENT
+            + TOAST_CONTAINER_ELEMENT
             + Cow::Owned(scripts)
             + "</body>"
-            + "</html>"
+            + "</html>";
+
+        size_report.document = document.len();
+
+        (document.into_owned(), size_report)
+    }
+}
+
+impl UiPaint for Shell {
+    fn to_html(&self) -> Cow<str> {
+        Cow::Owned(self.to_html_with_size_report().0)
     }
 }
 
-/// Whether it is a dark or light theme or whether it will respect system settings
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// The size in bytes of the document built by the [Shell], see [Shell::size_report]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ShellSizeReport {
+    /// The whole document
+    pub document: usize,
+    /// The `<style>` elements without the color palettes and fonts
+    pub styles: usize,
+    /// The scripts including the [PUPPETEER_RUNTIME_SCRIPT]
+    pub scripts: usize,
+    /// The `@font-face` rules and the font preload scripts
+    pub fonts: usize,
+    /// The number of head links, styles, fonts and scripts that were added more than once
+    pub duplicates: usize,
+}
+
+/// The parts of the document built by [Shell::bundle]
+struct ShellBundle {
+    head_links: String,
+    styles: String,
+    fonts: String,
+    font_preloads: String,
+    runtime: String,
+    head_scripts: String,
+    scripts: String,
+    duplicates: usize,
+}
+
+/// The indices of the entries whose blake3 hash of the trimmed content is not in `seen`
+fn unique(
+    entries: &[impl AsRef<str>],
+    seen: &mut HashSet<blake3::Hash>,
+    duplicates: &mut usize,
+) -> Vec<usize> {
+    (0..entries.len())
+        .filter(|index| {
+            let added = seen.insert(blake3::hash(entries[*index].as_ref().trim().as_bytes()));
+            *duplicates += usize::from(!added);
+
+            added
+        })
+        .collect()
+}
+
+/// Like [unique] but keeps the last copy of the entries added more than once,
+/// so that a style added again still wins the cascade
+fn unique_last(entries: &[impl AsRef<str>], duplicates: &mut usize) -> Vec<usize> {
+    let last = entries.len().saturating_sub(1);
+    let reversed = entries.iter().rev().collect::<Vec<_>>();
+
+    unique(&reversed, &mut HashSet::new(), duplicates)
+        .into_iter()
+        .rev()
+        .map(|index| last - index)
+        .collect()
+}
+
+/// The entries at the indices
+fn concat(entries: &[impl AsRef<str>], indices: Vec<usize>) -> String {
+    indices
+        .into_iter()
+        .map(|index| entries[index].as_ref())
+        .collect()
+}
+
+/// Whether it is a dark or light theme or whether it will respect system settings.
+/// Switch the theme at runtime using [crate::ModifyView::SetTheme], which swaps the
+/// CSS custom properties of the [ColorPalette] without painting the view again
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Theme {
     /// Dark Theme
     Dark,
     /// Light Theme
     Light,
     /// The system theme
+    #[default]
     System,
 }
 
@@ -208,6 +788,25 @@ impl Theme {
             Self::System => Self::Dark,
         }
     }
+
+    /// The theme applied to the document. [Theme::System] resolves to the `system` theme
+    /// and to [Theme::Light] if the theme of the system is unknown
+    pub fn resolve(&self, system: Theme) -> Theme {
+        match (self, system) {
+            (Self::System, Self::Dark) => Self::Dark,
+            (Self::System, _) => Self::Light,
+            _ => *self,
+        }
+    }
+
+    /// The value of the `data-pp-theme` attribute of the document
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Dark => "dark",
+            Self::Light => "light",
+            Self::System => "system",
+        }
+    }
 }
 
 impl From<WryTheme> for Theme {
@@ -219,3 +818,116 @@ impl From<WryTheme> for Theme {
         }
     }
 }
+
+#[cfg(test)]
+mod shell_checks {
+    use super::*;
+    use crate::{DomSimulator, ModifyView};
+
+    #[test]
+    fn themes() {
+        assert_eq!(Theme::Dark, Theme::System.resolve(Theme::Dark));
+        assert_eq!(Theme::Light, Theme::System.resolve(Theme::System));
+        assert_eq!(Theme::Light, Theme::Light.resolve(Theme::Dark));
+
+        let shell = Shell::new().with_theme(Theme::Dark);
+        assert!(shell
+            .to_html()
+            .contains(r#":root[data-pp-theme="dark"] {--pp-primary: #1E1E1E;"#));
+
+        let mut dom = DomSimulator::from_shell(&shell);
+        let theme = |dom: &DomSimulator| {
+            dom.select_one("html")
+                .unwrap()
+                .and_then(|html| html.attribute("data-pp-theme"))
+                .map(str::to_owned)
+        };
+        assert_eq!(Some("dark".to_owned()), theme(&dom));
+
+        dom.apply(&ModifyView::SetTheme(Theme::Light)).unwrap();
+        assert_eq!(Some("light".to_owned()), theme(&dom));
+        assert_eq!(
+            r#"window.puppeteer.setTheme("light");"#,
+            ModifyView::SetTheme(Theme::Light).to_html()
+        );
+    }
+
+    #[test]
+    fn documents() {
+        const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+        let shell = Shell::new()
+            .with_title("<Notes>")
+            .add_meta("description", "Take \"notes\"")
+            .with_favicon(&crate::StaticAsset {
+                name: "icon.png",
+                bytes: PNG,
+            })
+            .add_body_class("notes")
+            .add_body_class("compact")
+            .add_script(r#"<script>console.log("body");</script>"#.into())
+            .add_script_element(Script::source("/chart.js?a=1&b=2").defer().in_head())
+            .add_script_element(Script::inline("console.log('</script>')").module());
+        let html = shell.to_html();
+
+        assert!(html.contains(concat!(
+            r#"<meta name="description" content="Take &quot;notes&quot;">"#,
+            r#"<title>&lt;Notes&gt;</title>"#,
+            r#"<link rel="icon" type="image/png" href="data:image/png;base64,"#,
+        )));
+        assert!(html.contains(r#"<script defer src="/chart.js?a=1&amp;b=2"></script><style>"#));
+        assert!(html.contains(concat!(
+            r#"<body class="notes compact"><div id="puppeteer_app"></div>"#,
+            r#"<div id="pp-toasts" class="pp-toasts" aria-live="polite"></div>"#,
+            r#"<script>console.log("body");</script>"#,
+            r#"<script type="module">console.log('<\/script>')</script></body></html>"#,
+        )));
+        assert_eq!(1, html.matches("<title>").count());
+
+        let mut dom = DomSimulator::from_shell(&shell);
+        dom.apply(&ModifyView::SetTitle("Notes - Draft".to_owned()))
+            .unwrap();
+        assert_eq!(
+            "Notes - Draft",
+            dom.select_one("title").unwrap().unwrap().text_content()
+        );
+    }
+
+    #[test]
+    fn bundles() {
+        let shell = Shell::new()
+            .add_style("nav  a { color: red; }")
+            .add_style("\nnav  a { color: red; }\n")
+            .add_const_scripts([
+                crate::TITLE_BAR_SCRIPT,
+                crate::DEFAULT_WINDOW_ACTIONS_SCRIPT,
+            ])
+            .add_script_element(Script::inline("// greet\n    console.log('hi')"))
+            .add_script_element(Script::inline("// greet\n    console.log('hi')").in_head());
+        let html = shell.to_html();
+
+        assert_eq!(1, html.matches("nav  a { color: red; }").count());
+        assert_eq!(2, html.matches("postMessage('drag_window')").count());
+        assert_eq!(1, html.matches("console.log('hi')").count());
+
+        let report = shell.size_report();
+        assert_eq!(3, report.duplicates);
+        assert_eq!(html.len(), report.document);
+
+        let cascade = Shell::new()
+            .add_style("a {color: red;}")
+            .add_style("a {color: blue;}")
+            .add_style("a {color: red;}");
+        assert!(cascade
+            .to_html()
+            .contains("<style>a {color: blue;}a {color: red;}</style>"));
+
+        let minified = shell.minify();
+        let html = minified.to_html();
+        assert!(html.contains("<style>:root {"));
+        assert!(html.contains("nav a{color:red}</style>"));
+        assert!(html.contains("<script>console.log('hi')</script><style>"));
+        assert!(!html.contains("\n    "));
+        assert!(minified.size_report().document < report.document);
+    }
+}
@@ -0,0 +1,458 @@
+use crate::{utils::js_string, AppEnvironment, OsType, PuppeteerError, PuppeteerResult};
+
+/// The prefix of IPC messages sent by the keyboard shortcut dispatcher.
+/// Messages starting with this prefix are never passed to `Puppeteer::parse()`
+pub const SHORTCUT_IPC_PREFIX: &str = "puppeteer:shortcut>";
+
+/// The function called to create the custom event when a keyboard shortcut is pressed
+pub type ShortcutHandler<T> = fn() -> T;
+
+/// The keydown listener injected into the [crate::Shell] which sends the registered
+/// keyboard shortcuts using IPC. `PUPPETEER_SHORTCUT_BINDINGS` and `PUPPETEER_SHORTCUT_PREFIX`
+/// are replaced by the [Shortcuts] registry.
+const SHORTCUT_DISPATCHER_SCRIPT: &str = r#"
+<script>
+(function () {
+    const bindings = PUPPETEER_SHORTCUT_BINDINGS;
+
+    const keyName = (event) => {
+        const key = event.key.toLowerCase();
+
+        // Letters follow the keyboard layout so `Ctrl+Z` is the key labelled Z on AZERTY or Dvorak
+        if (/^[a-z]$/.test(key)) {
+            return key;
+        }
+        // Layouts without latin letters and `Alt` on Apple devices change the letter so the physical key is used
+        if (event.code.startsWith("Key")) {
+            return event.code.slice(3).toLowerCase();
+        }
+        if (event.code.startsWith("Digit")) {
+            return event.code.slice(5);
+        }
+
+        return key === " " ? "space" : key;
+    };
+
+    document.addEventListener("keydown", (event) => {
+        const key = keyName(event);
+        const combo = (shift) => [
+            event.ctrlKey && "ctrl",
+            event.altKey && "alt",
+            shift && "shift",
+            event.metaKey && "meta",
+            key,
+        ].filter(Boolean).join("+");
+
+        // Symbols like `+` need `Shift` on most layouts so `Ctrl+Plus` also matches `Ctrl+Shift+=`
+        const combos = [combo(event.shiftKey)];
+        if (event.shiftKey && key.length === 1 && !/[a-z0-9]/.test(key)) {
+            combos.push(combo(false));
+        }
+
+        const matches = combos.flatMap((combo) => bindings.filter((binding) => binding.combo === combo));
+        const focused = document.activeElement;
+        const scoped = matches.find((binding) => {
+            const scope = binding.scope === null ? null : document.getElementById(binding.scope);
+
+            return scope !== null && scope.contains(focused);
+        });
+        const binding = scoped || matches.find((binding) => binding.scope === null);
+
+        if (binding) {
+            event.preventDefault();
+            window.ipc.postMessage(PUPPETEER_SHORTCUT_PREFIX + binding.id);
+        }
+    });
+})();
+</script>
+"#;
+
+/// A key combination like `Ctrl+S` or `CmdOrCtrl+Shift+P`.
+///
+/// The modifiers are:
+/// - `Ctrl` or `Control` for the control key
+/// - `Alt` or `Option` for the alt key
+/// - `Shift` for the shift key
+/// - `Cmd`, `Command`, `Meta` or `Super` for the command key on Apple devices
+///   and the Windows key on other devices
+/// - `CmdOrCtrl`, `CommandOrControl` or `Primary` for the command key on Apple devices
+///   and the control key on other devices
+///
+/// Modifiers and keys are not case sensitive. Letters match the key labelled with the letter
+/// in the keyboard layout of the user, other keys like digits match the physical key.
+/// Symbols like `Plus` also match when `Shift` is needed to type them.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
+pub struct Accelerator {
+    ctrl: bool,
+    alt: bool,
+    shift: bool,
+    meta: bool,
+    key: String,
+}
+
+impl Accelerator {
+    /// Parse the accelerator resolving platform specific modifiers using the [OsType]
+    pub fn parse(accelerator: &str, os: OsType) -> PuppeteerResult<Self> {
+        let invalid = || PuppeteerError::InvalidShortcut(accelerator.to_owned());
+        let primary_is_meta = matches!(os, OsType::MacOs | OsType::Ios);
+
+        let mut outcome = Accelerator {
+            ctrl: false,
+            alt: false,
+            shift: false,
+            meta: false,
+            key: String::default(),
+        };
+
+        for token in accelerator.split('+').map(|token| token.trim()) {
+            let token = token.to_lowercase();
+
+            match token.as_str() {
+                "ctrl" | "control" => outcome.ctrl = true,
+                "alt" | "option" => outcome.alt = true,
+                "shift" => outcome.shift = true,
+                "cmd" | "command" | "meta" | "super" => outcome.meta = true,
+                "cmdorctrl" | "commandorcontrol" | "primary" => {
+                    if primary_is_meta {
+                        outcome.meta = true
+                    } else {
+                        outcome.ctrl = true
+                    }
+                }
+                _ => {
+                    if !outcome.key.is_empty() {
+                        return Err(invalid());
+                    }
+
+                    outcome.key = Accelerator::key_name(&token).ok_or_else(invalid)?;
+                }
+            }
+        }
+
+        if outcome.key.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(outcome)
+    }
+
+    /// The key without the modifiers
+    pub fn key(&self) -> &str {
+        self.key.as_str()
+    }
+
+    /// The canonical form of the key combination as computed by the dispatcher script,
+    /// for example `ctrl+shift+p`
+    pub fn combo(&self) -> String {
+        [
+            (self.ctrl, "ctrl"),
+            (self.alt, "alt"),
+            (self.shift, "shift"),
+            (self.meta, "meta"),
+        ]
+        .iter()
+        .filter(|(active, _)| *active)
+        .map(|(_, modifier)| *modifier)
+        .chain([self.key.as_str()])
+        .collect::<Vec<&str>>()
+        .join("+")
+    }
+
+    /// Match the name of the key to the name used by `KeyboardEvent.key`
+    fn key_name(token: &str) -> Option<String> {
+        let key = match token {
+            "esc" => "escape",
+            "return" => "enter",
+            "del" => "delete",
+            "up" => "arrowup",
+            "down" => "arrowdown",
+            "left" => "arrowleft",
+            "right" => "arrowright",
+            "pgup" => "pageup",
+            "pgdn" => "pagedown",
+            "plus" => "+",
+            "space" | "enter" | "escape" | "tab" | "backspace" | "delete" | "insert" | "home"
+            | "end" | "pageup" | "pagedown" | "arrowup" | "arrowdown" | "arrowleft"
+            | "arrowright" => token,
+            _ => {
+                let is_function_key = token
+                    .strip_prefix('f')
+                    .and_then(|number| number.parse::<u8>().ok())
+                    .map(|number| (1..=24).contains(&number))
+                    .unwrap_or(false);
+
+                if !is_function_key && token.chars().count() != 1 {
+                    return Option::None;
+                }
+
+                token
+            }
+        };
+
+        Some(key.to_owned())
+    }
+}
+
+/// A keyboard shortcut bound to a custom event
+pub struct Shortcut<T> {
+    accelerator: Accelerator,
+    scope: Option<String>,
+    handler: ShortcutHandler<T>,
+}
+
+impl<T> Shortcut<T> {
+    /// The key combination
+    pub fn accelerator(&self) -> &Accelerator {
+        &self.accelerator
+    }
+
+    /// The `id` of the element that must contain the focused element
+    /// for the shortcut to be triggered
+    pub fn scope(&self) -> Option<&str> {
+        self.scope.as_deref()
+    }
+}
+
+impl<T> core::fmt::Debug for Shortcut<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Shortcut")
+            .field("accelerator", &self.accelerator)
+            .field("scope", &self.scope)
+            .finish()
+    }
+}
+
+/// The registry of keyboard shortcuts mapped to custom events.
+/// A single keydown listener is injected into the [crate::Shell] to dispatch all shortcuts.
+///
+/// Shortcuts scoped to an element `id` are only triggered when the focused element is inside
+/// the element and take priority over shortcuts that are not scoped.
+///
+/// #### Example
+/// ```rust,ignore
+/// let shortcuts = Shortcuts::new(&AppEnvironment::init())
+///     .register("CmdOrCtrl+S", || AppTest::Save)?
+///     .register_scoped("Ctrl+Enter", "editor", || AppTest::Submit)?;
+///
+/// PuppeteerApp::<AppTest>::init("Puppeteer Test App").with_shortcuts(shortcuts);
+/// ```
+pub struct Shortcuts<T> {
+    os: OsType,
+    bindings: Vec<Shortcut<T>>,
+}
+
+impl<T> Shortcuts<T> {
+    /// Initialize the registry for the [AppEnvironment::os], which decides whether
+    /// `CmdOrCtrl` is the command key or the control key
+    pub fn new(env: &AppEnvironment) -> Self {
+        Shortcuts::with_os(env.os)
+    }
+
+    /// Initialize the registry for a specific operating system
+    pub fn with_os(os: OsType) -> Self {
+        Shortcuts {
+            os,
+            bindings: Vec::default(),
+        }
+    }
+
+    /// Bind a shortcut that is triggered anywhere in the app.
+    /// Returns an error if the accelerator is invalid or already bound.
+    pub fn register(self, accelerator: &str, handler: ShortcutHandler<T>) -> PuppeteerResult<Self> {
+        self.add(accelerator, Option::None, handler)
+    }
+
+    /// Bind a shortcut that is only triggered when the focused element is inside the element
+    /// with the `id`. Returns an error if the accelerator is invalid or already bound in this scope.
+    pub fn register_scoped(
+        self,
+        accelerator: &str,
+        id: &str,
+        handler: ShortcutHandler<T>,
+    ) -> PuppeteerResult<Self> {
+        self.add(accelerator, Some(id.to_owned()), handler)
+    }
+
+    fn add(
+        mut self,
+        accelerator: &str,
+        scope: Option<String>,
+        handler: ShortcutHandler<T>,
+    ) -> PuppeteerResult<Self> {
+        let accelerator = Accelerator::parse(accelerator, self.os)?;
+
+        if self
+            .bindings
+            .iter()
+            .any(|binding| binding.accelerator == accelerator && binding.scope == scope)
+        {
+            return Err(PuppeteerError::ShortcutConflict(accelerator.combo()));
+        }
+
+        self.bindings.push(Shortcut {
+            accelerator,
+            scope,
+            handler,
+        });
+
+        Ok(self)
+    }
+
+    /// All the registered shortcuts
+    pub fn shortcuts(&self) -> &[Shortcut<T>] {
+        self.bindings.as_slice()
+    }
+
+    /// Is the registry empty
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+
+    /// Create the custom event for an IPC message sent by the dispatcher script.
+    /// Returns `None` if the message was not sent by the dispatcher script.
+    pub fn event(&self, message: &str) -> Option<T> {
+        let index = message
+            .strip_prefix(SHORTCUT_IPC_PREFIX)?
+            .parse::<usize>()
+            .ok()?;
+
+        self.bindings.get(index).map(|binding| (binding.handler)())
+    }
+
+    /// Build the keydown dispatcher script to add to the [crate::Shell]
+    pub fn script(&self) -> String {
+        let bindings = self
+            .bindings
+            .iter()
+            .enumerate()
+            .map(|(index, binding)| {
+                let scope = binding
+                    .scope
+                    .as_deref()
+                    .map(js_string)
+                    .unwrap_or_else(|| "null".to_owned());
+
+                format!(
+                    "{{combo:{},scope:{},id:{}}}",
+                    js_string(&binding.accelerator.combo()),
+                    scope,
+                    index
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(",");
+
+        SHORTCUT_DISPATCHER_SCRIPT
+            .replace(
+                "PUPPETEER_SHORTCUT_BINDINGS",
+                &(String::from("[") + &bindings + "]"),
+            )
+            .replace("PUPPETEER_SHORTCUT_PREFIX", &js_string(SHORTCUT_IPC_PREFIX))
+    }
+}
+
+impl<T> Default for Shortcuts<T> {
+    fn default() -> Self {
+        Shortcuts::new(&AppEnvironment::init())
+    }
+}
+
+impl<T> core::fmt::Debug for Shortcuts<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Shortcuts")
+            .field("os", &self.os)
+            .field("bindings", &self.bindings)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod shortcut_checks {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Event {
+        Save,
+        Palette,
+        Submit,
+    }
+
+    #[test]
+    fn accelerators() {
+        assert_eq!(
+            "ctrl+shift+p",
+            Accelerator::parse("Shift+Ctrl+P", OsType::Linux)
+                .unwrap()
+                .combo()
+        );
+        assert_eq!(
+            "meta+s",
+            Accelerator::parse("CmdOrCtrl+S", OsType::MacOs)
+                .unwrap()
+                .combo()
+        );
+        assert_eq!(
+            "ctrl+s",
+            Accelerator::parse("CmdOrCtrl+S", OsType::Windows)
+                .unwrap()
+                .combo()
+        );
+        assert_eq!(
+            "alt+arrowleft",
+            Accelerator::parse("Alt+Left", OsType::Linux)
+                .unwrap()
+                .combo()
+        );
+        assert_eq!(
+            "f12",
+            Accelerator::parse("F12", OsType::Linux).unwrap().combo()
+        );
+        assert!(Accelerator::parse("Ctrl+Shift", OsType::Linux).is_err());
+        assert!(Accelerator::parse("Ctrl+S+P", OsType::Linux).is_err());
+        assert!(Accelerator::parse("Ctrl+Foo", OsType::Linux).is_err());
+        assert!(Accelerator::parse("F25", OsType::Linux).is_err());
+    }
+
+    #[test]
+    fn registry() {
+        let shortcuts = Shortcuts::with_os(OsType::Linux)
+            .register("CmdOrCtrl+S", || Event::Save)
+            .unwrap()
+            .register("Ctrl+Shift+P", || Event::Palette)
+            .unwrap()
+            .register_scoped("Ctrl+S", "editor", || Event::Submit)
+            .unwrap();
+
+        assert!(matches!(
+            Shortcuts::with_os(OsType::Linux)
+                .register("CmdOrCtrl+S", || Event::Save)
+                .unwrap()
+                .register("ctrl+s", || Event::Palette),
+            Err(PuppeteerError::ShortcutConflict(_))
+        ));
+
+        // `CmdOrCtrl` resolves to the command key so there is no conflict on MacOS
+        let mac = AppEnvironment {
+            os: OsType::MacOs,
+            ..AppEnvironment::init()
+        };
+        assert!(Shortcuts::new(&mac)
+            .register("CmdOrCtrl+S", || Event::Save)
+            .unwrap()
+            .register("Ctrl+S", || Event::Palette)
+            .is_ok());
+
+        assert_eq!(Some(Event::Save), shortcuts.event("puppeteer:shortcut>0"));
+        assert_eq!(Some(Event::Submit), shortcuts.event("puppeteer:shortcut>2"));
+        assert_eq!(None, shortcuts.event("puppeteer:shortcut>3"));
+        assert_eq!(None, shortcuts.event("inc"));
+
+        let script = shortcuts.script();
+        assert!(script.contains(
+            r#"const bindings = [{combo:"ctrl+s",scope:null,id:0},{combo:"ctrl+shift+p",scope:null,id:1},{combo:"ctrl+s",scope:"editor",id:2}];"#
+        ));
+        assert!(
+            script.contains(r#"window.ipc.postMessage("puppeteer:shortcut\u003e" + binding.id);"#)
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{ColorPalette, StaticCowStr, StaticStr, UiPaint};
+use crate::{StaticCowStr, StaticStr, UiPaint};
 
 /// The default script to use when a drag event is done on the title bar
 pub const TITLE_BAR_SCRIPT: &str = r#"
@@ -95,12 +95,9 @@ impl UiPaint for TitleBar {
         let title_bar_open = StaticCowStr::Borrowed(r#"<div id=""#) + self.id + r#"">"#;
         let div_close = "</div>";
         let drag_region = r#"<div class="drag-region">"#;
-        let minimize_button =
-            r#"<div class="titlebar-button" onclick="window.ipc.postMessage('minimize')">"#;
-        let maximize_button =
-            r#"<div class="titlebar-button" onclick="window.ipc.postMessage('maximize')">"#;
-        let close_button =
-            r#"<div class="titlebar-button" onclick="window.ipc.postMessage('close_window')">"#;
+        let minimize_button = r#"<div class="titlebar-button" data-pp-on-click="minimize">"#;
+        let maximize_button = r#"<div class="titlebar-button" data-pp-on-click="maximize">"#;
+        let close_button = r#"<div class="titlebar-button" data-pp-on-click="close_window">"#;
 
         title_bar_open
             + drag_region
@@ -131,7 +128,7 @@ impl Default for TitleBar {
     }
 }
 
-/// Create CSS for the title bar based on the color palette
+/// Create CSS for the title bar based on the CSS custom properties of the [crate::ColorPalette]
 #[derive(Debug, PartialEq, Eq, Clone, PartialOrd, Ord)]
 pub struct TitleBarCSS {
     /// The background color of the window icon
@@ -155,19 +152,9 @@ pub struct TitleBarCSS {
 }
 
 impl TitleBarCSS {
-    /// Initialize using the [ColorPalette]
-    pub fn new(palette: &ColorPalette) -> Self {
-        TitleBarCSS {
-            background_color: palette.primary,
-            background_color_hover: palette.secondary,
-            padding: "10px",
-            padding_hover: "10px",
-            svg_icon_width: "20px",
-            svg_icon_width_hover: "20px",
-            svg_icon_color: palette.secondary,
-            svg_icon_color_hover: palette.primary,
-            extra_css: "",
-        }
+    /// Initialize using the colors of the [crate::ColorPalette]
+    pub fn new() -> Self {
+        TitleBarCSS::default()
     }
 
     /// Build CSS to use to insert to the stylesheet
@@ -250,6 +237,22 @@ impl TitleBarCSS {
     }
 }
 
+impl Default for TitleBarCSS {
+    fn default() -> Self {
+        TitleBarCSS {
+            background_color: "var(--pp-primary)",
+            background_color_hover: "var(--pp-tertiary)",
+            padding: "10px",
+            padding_hover: "10px",
+            svg_icon_width: "20px",
+            svg_icon_width_hover: "20px",
+            svg_icon_color: "var(--pp-secondary)",
+            svg_icon_color_hover: "var(--pp-text)",
+            extra_css: "",
+        }
+    }
+}
+
 const MINIMIZE: &str = r#"
 <svg enable-background="new 0 0 32 32" height="32" viewBox="0 0 16 16" width="32"
     xmlns="http://www.w3.org/2000/svg">
@@ -46,6 +46,23 @@ pub trait Puppeteer {
     /// This is used to handle errors. It is async so that I/O can be used like to log to a file.
     /// It returns a [ModifyView] which can display an error message to the user
     async fn error_handler(error: impl std::error::Error + Send) -> ModifyView;
+
+    /// Called after the user changes an element bound to a field of the [crate::Bindings].
+    /// Return an event to handle it using `Puppeteer::event_handler()`.
+    /// This defaults to `None`
+    fn on_binding_change(_change: &crate::BindingChange) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// Called after the locale changed using [crate::ModifyView::SetLocale] when the app
+    /// does not use a [crate::Router], which loads the current page again instead.
+    /// Return the view painted in the new locale. This defaults to [ModifyView::Skip]
+    async fn on_locale_change(_app_env: &ActiveAppEnv) -> ModifyView {
+        ModifyView::Skip
+    }
 }
 
 /// Trait that ensures a type can be converted to code that can be rendered into current view
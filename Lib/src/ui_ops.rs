@@ -1,4 +1,8 @@
-use crate::{PuppeteerError, PuppeteerResult, UiPaint};
+use crate::{
+    utils::js_string, BindingChange, ClipboardRequest, ComponentMessage, DomPatch, HotReload,
+    ListRequest, ModalResponse, PuppeteerError, PuppeteerResult, Script, ScriptLoading,
+    ScriptPlacement, Theme, Toast, UiPaint,
+};
 use std::borrow::Cow;
 use tao::{
     dpi::{PhysicalPosition, PhysicalSize},
@@ -27,6 +31,74 @@ pub enum UiEvent<T: crate::Puppeteer + 'static + Send + Sync> {
     Error(PuppeteerError),
     /// Modify the webview with new contents
     Paint(ModifyView),
+    /// A clipboard operation requested using an IPC message
+    Clipboard(ClipboardRequest),
+    /// Open the devtools if they are closed or close them if they are open
+    ToggleDevtools,
+    /// A file watched in developer mode has changed
+    HotReload(HotReload),
+    /// A message sent to a mounted [crate::Component]
+    Component(ComponentMessage),
+    /// The user changed an element bound to a field of the [crate::Bindings]
+    Binding(BindingChange),
+    /// The user closed a modal opened using the [crate::Modals]
+    Modal(ModalResponse),
+    /// A [crate::VirtualList] needs rows
+    VirtualList(ListRequest),
+}
+
+/// The nodes targeted by a [ModifyView] operation
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub enum Target {
+    /// The node with the `id`
+    Id(String),
+    /// All the nodes matching the CSS selector
+    Selector(String),
+}
+
+impl Target {
+    /// Target the node with the `id`
+    pub fn id(id: &str) -> Self {
+        Self::Id(id.to_owned())
+    }
+
+    /// Target all the nodes matching the CSS selector
+    pub fn selector(selector: &str) -> Self {
+        Self::Selector(selector.to_owned())
+    }
+
+    /// The JavaScript object used by [crate::PUPPETEER_RUNTIME_SCRIPT] to find the nodes
+    fn to_js(&self) -> String {
+        match self {
+            Self::Id(id) => String::from("{id:") + &js_string(id) + "}",
+            Self::Selector(selector) => String::from("{selector:") + &js_string(selector) + "}",
+        }
+    }
+}
+
+/// Where content is inserted relative to the target node
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum InsertPosition {
+    /// Before the target node
+    BeforeBegin,
+    /// Inside the target node before its first child
+    AfterBegin,
+    /// Inside the target node after its last child
+    BeforeEnd,
+    /// After the target node
+    AfterEnd,
+}
+
+impl InsertPosition {
+    /// The position as used by `Element.insertAdjacentHTML()`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BeforeBegin => "beforebegin",
+            Self::AfterBegin => "afterbegin",
+            Self::BeforeEnd => "beforeend",
+            Self::AfterEnd => "afterend",
+        }
+    }
 }
 
 /// Used to modify the view which can be a WebView
@@ -65,6 +137,162 @@ pub enum ModifyView {
         /// Callback function to use to send event to update node based on an operation on the text content
         func: JsCallback,
     },
+    /// Insert HTML content relative to the target nodes without replacing their children
+    InsertAdjacent {
+        /// The nodes to insert the content relative to
+        target: Target,
+        /// Where the content is inserted
+        position: InsertPosition,
+        /// The HTML content
+        content: String,
+    },
+    /// Remove the target nodes from the document
+    RemoveNode {
+        /// The nodes to remove
+        target: Target,
+    },
+    /// Add or replace an attribute of the target nodes
+    SetAttribute {
+        /// The nodes to modify
+        target: Target,
+        /// The name of the attribute
+        name: String,
+        /// The value of the attribute
+        value: String,
+    },
+    /// Remove an attribute from the target nodes
+    RemoveAttribute {
+        /// The nodes to modify
+        target: Target,
+        /// The name of the attribute
+        name: String,
+    },
+    /// Add a class to the target nodes
+    AddClass {
+        /// The nodes to modify
+        target: Target,
+        /// The class name
+        class: String,
+    },
+    /// Remove a class from the target nodes
+    RemoveClass {
+        /// The nodes to modify
+        target: Target,
+        /// The class name
+        class: String,
+    },
+    /// Add a class to the target nodes that don't have it and remove it from those that do
+    ToggleClass {
+        /// The nodes to modify
+        target: Target,
+        /// The class name
+        class: String,
+    },
+    /// Set an inline style property like `color` or a custom property like `--pp-primary`
+    SetStyle {
+        /// The nodes to modify
+        target: Target,
+        /// The CSS property in kebab case
+        property: String,
+        /// The value of the CSS property
+        value: String,
+    },
+    /// Set the value of `<input>`, `<textarea>` or `<select>` nodes
+    /// without losing the focus or the cursor position of other nodes
+    SetValue {
+        /// The nodes to modify
+        target: Target,
+        /// The value
+        value: String,
+    },
+    /// Replace the children of the target nodes with text. The text is never parsed as HTML
+    SetText {
+        /// The nodes to modify
+        target: Target,
+        /// The text content
+        text: String,
+    },
+    /// Perform several operations in a single script so that the webview
+    /// never renders the intermediate states. The results of the compute
+    /// operations are passed to their callbacks in the order they were added.
+    /// [ModifyView::SetTheme], [ModifyView::SetTitle] and [ModifyView::SetLocale] also change
+    /// the app, so they are performed after the script of the batch like outside a batch.
+    /// Window operations, navigation and [ModifyView::Skip] are ignored inside a batch
+    Batch(Vec<ModifyView>),
+    /// Apply the patches computed by [crate::VDom::render] to the tree mounted in the node with the `id`
+    Patch {
+        /// The `id` of the mount point
+        id: String,
+        /// The patches to apply in order
+        patches: Vec<DomPatch>,
+    },
+    /// Push the value of a field into all the elements bound to it. See [crate::Bindings]
+    SetBinding {
+        /// The name of the field
+        field: String,
+        /// The new value
+        value: String,
+    },
+    /// Navigate to the path using the [crate::Router] of the app
+    Navigate(String),
+    /// Go to the previous page in the history of the [crate::Router]
+    Back,
+    /// Go to the next page in the history of the [crate::Router]
+    Forward,
+    /// Change the locale of the [crate::Localization], update the `lang` and `dir`
+    /// attributes of the document and paint the view again
+    SetLocale(String),
+    /// Show a modal in an overlay that traps the focus. Use the [crate::Modals]
+    /// to open modals and wait for the response of the user
+    ShowModal {
+        /// The ID of the modal sent back with the response
+        id: u64,
+        /// The HTML content of the modal
+        content: String,
+    },
+    /// Stack a [Toast] in the [crate::TOAST_CONTAINER_ELEMENT]
+    Toast(Toast),
+    /// Switch between the light and dark [crate::ColorPalette] of the [crate::Shell].
+    /// [Theme::System] follows the theme of the operating system until another theme is set
+    SetTheme(Theme),
+    /// Change the title of the window and the `<title>` of the document.
+    /// Inside a [ModifyView::Batch] only the `<title>` of the document is changed
+    SetTitle(String),
+    /// Add a `<style>` element named `name` to the `<head>`, or replace the CSS
+    /// of the style already added with the same name
+    InjectStyle {
+        /// The name of the style
+        name: String,
+        /// The CSS of the style
+        css: String,
+    },
+    /// Remove the style added using [ModifyView::InjectStyle] with the name
+    RemoveStyle(String),
+    /// Add a [Script] named `name` to the `<head>` or the end of the `<body>` depending on its
+    /// [ScriptPlacement]. Nothing happens if a script with the same name, URL and content was
+    /// already added, otherwise the previous script is replaced and the new one is run.
+    /// Replacing or removing a script does not undo what it already did.
+    /// Scripts are given the nonce of the [crate::ContentSecurityPolicy] but their URL
+    /// must be allowed in its `script-src`
+    InjectScript {
+        /// The name of the script
+        name: String,
+        /// The script
+        script: Script,
+    },
+    /// Remove the script added using [ModifyView::InjectScript] with the name
+    RemoveScript(String),
+    /// Paint rows of a [crate::VirtualList] loaded using [crate::VirtualLists::rows]
+    VirtualRows {
+        /// The `id` of the list
+        id: String,
+        /// The total number of rows, used to size the scrollbar
+        total: usize,
+        /// The index of the first row
+        start: usize,
+        /// The HTML of the rows
+        rows: String,
+    },
 }
 
 impl ModifyView {
@@ -96,36 +324,374 @@ impl ModifyView {
             func,
         }
     }
+
+    /// Insert content relative to the target nodes
+    pub fn insert_adjacent(
+        target: Target,
+        position: InsertPosition,
+        content: impl UiPaint,
+    ) -> Self {
+        Self::InsertAdjacent {
+            target,
+            position,
+            content: content.to_html().to_string(),
+        }
+    }
+
+    /// Insert content inside the target nodes after their last child
+    pub fn append(target: Target, content: impl UiPaint) -> Self {
+        Self::insert_adjacent(target, InsertPosition::BeforeEnd, content)
+    }
+
+    /// Insert content inside the target nodes before their first child
+    pub fn prepend(target: Target, content: impl UiPaint) -> Self {
+        Self::insert_adjacent(target, InsertPosition::AfterBegin, content)
+    }
+
+    /// Remove the target nodes
+    pub fn remove_node(target: Target) -> Self {
+        Self::RemoveNode { target }
+    }
+
+    /// Add or replace an attribute of the target nodes
+    pub fn set_attribute(target: Target, name: &str, value: &str) -> Self {
+        Self::SetAttribute {
+            target,
+            name: name.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+
+    /// Remove an attribute from the target nodes
+    pub fn remove_attribute(target: Target, name: &str) -> Self {
+        Self::RemoveAttribute {
+            target,
+            name: name.to_owned(),
+        }
+    }
+
+    /// Add a class to the target nodes
+    pub fn add_class(target: Target, class: &str) -> Self {
+        Self::AddClass {
+            target,
+            class: class.to_owned(),
+        }
+    }
+
+    /// Remove a class from the target nodes
+    pub fn remove_class(target: Target, class: &str) -> Self {
+        Self::RemoveClass {
+            target,
+            class: class.to_owned(),
+        }
+    }
+
+    /// Toggle a class of the target nodes
+    pub fn toggle_class(target: Target, class: &str) -> Self {
+        Self::ToggleClass {
+            target,
+            class: class.to_owned(),
+        }
+    }
+
+    /// Set an inline style property of the target nodes
+    pub fn set_style(target: Target, property: &str, value: &str) -> Self {
+        Self::SetStyle {
+            target,
+            property: property.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+
+    /// Set the value of the target input nodes
+    pub fn set_value(target: Target, value: &str) -> Self {
+        Self::SetValue {
+            target,
+            value: value.to_owned(),
+        }
+    }
+
+    /// Set the text content of the target nodes
+    pub fn set_text(target: Target, text: &str) -> Self {
+        Self::SetText {
+            target,
+            text: text.to_owned(),
+        }
+    }
+
+    /// Add or replace the style named `name`
+    pub fn inject_style(name: &str, css: &str) -> Self {
+        Self::InjectStyle {
+            name: name.to_owned(),
+            css: css.to_owned(),
+        }
+    }
+
+    /// Remove the style named `name`
+    pub fn remove_style(name: &str) -> Self {
+        Self::RemoveStyle(name.to_owned())
+    }
+
+    /// Add or replace the script named `name`
+    pub fn inject_script(name: &str, script: Script) -> Self {
+        Self::InjectScript {
+            name: name.to_owned(),
+            script,
+        }
+    }
+
+    /// Remove the script named `name`
+    pub fn remove_script(name: &str) -> Self {
+        Self::RemoveScript(name.to_owned())
+    }
+
+    /// Perform the operations in a single script
+    pub fn batch(views: impl IntoIterator<Item = ModifyView>) -> Self {
+        Self::Batch(views.into_iter().collect())
+    }
+
+    /// Perform `next` in the same script after the operations of [Self]
+    pub fn then(self, next: ModifyView) -> Self {
+        match self {
+            Self::Batch(mut views) => {
+                views.push(next);
+
+                Self::Batch(views)
+            }
+            _ => Self::Batch(vec![self, next]),
+        }
+    }
+}
+
+/// Calls a function of [crate::PUPPETEER_RUNTIME_SCRIPT] with JSON encoded arguments
+fn runtime_call(function: &str, arguments: &[&str]) -> String {
+    runtime_call_encoded(
+        function,
+        arguments.iter().map(|argument| js_string(argument)),
+    )
+}
+
+/// Calls a function of [crate::PUPPETEER_RUNTIME_SCRIPT] that modifies the [Target] nodes
+fn target_call(function: &str, target: &Target, arguments: &[&str]) -> String {
+    runtime_call_encoded(
+        function,
+        [target.to_js()]
+            .into_iter()
+            .chain(arguments.iter().map(|argument| js_string(argument))),
+    )
+}
+
+fn runtime_call_encoded(function: &str, arguments: impl Iterator<Item = String>) -> String {
+    String::from("window.puppeteer.")
+        + function
+        + "("
+        + &arguments.collect::<Vec<String>>().join(",")
+        + ")"
+}
+
+impl ModifyView {
+    /// The JavaScript expression performing the operation.
+    /// Operations that are not performed in the webview return `None`
+    fn expression(&self) -> Option<String> {
+        let expression = match self {
+            Self::ReplaceApp(content) => runtime_call("replaceApp", &[content]),
+            Self::ReplaceNodeWithId { id, content } => runtime_call("replaceNode", &[id, content]),
+            Self::CloseWindow
+            | Self::MaximizeWindow
+            | Self::MinimizeWindow
+            | Self::DragWindow
+            | Self::Skip
+            | Self::Navigate(_)
+            | Self::Back
+            | Self::Forward
+            | Self::SetLocale(_)
+            | Self::SetTheme(Theme::System) => return None,
+            Self::SetTheme(theme) => runtime_call("setTheme", &[theme.as_str()]),
+            Self::SetTitle(title) => runtime_call("setTitle", &[title]),
+            Self::InjectStyle { name, css } => runtime_call("injectStyle", &[name, css]),
+            Self::RemoveStyle(name) => runtime_call("removeStyle", &[name]),
+            Self::InjectScript { name, script } => runtime_call_encoded(
+                "injectScript",
+                [
+                    js_string(name),
+                    script.url().map_or_else(|| String::from("null"), js_string),
+                    js_string(script.content()),
+                    (script.loading() == ScriptLoading::Module).to_string(),
+                    (script.placement() == ScriptPlacement::Head).to_string(),
+                ]
+                .into_iter(),
+            ),
+            Self::RemoveScript(name) => runtime_call("removeScript", &[name]),
+            Self::Batch(views) => ModifyView::batch_expression(views),
+            Self::VirtualRows {
+                id,
+                total,
+                start,
+                rows,
+            } => runtime_call_encoded(
+                "setListRows",
+                [
+                    js_string(id),
+                    total.to_string(),
+                    start.to_string(),
+                    js_string(rows),
+                ]
+                .into_iter(),
+            ),
+            Self::Toast(toast) => runtime_call_encoded(
+                "showToast",
+                [
+                    js_string(&toast.to_html()),
+                    toast
+                        .timeout_ms()
+                        .map_or_else(|| String::from("null"), |timeout| timeout.to_string()),
+                ]
+                .into_iter(),
+            ),
+            Self::ShowModal { id, content } => runtime_call_encoded(
+                "showModal",
+                [id.to_string(), js_string(content)].into_iter(),
+            ),
+            Self::Patch { id, patches } => runtime_call_encoded(
+                "patch",
+                [
+                    js_string(id),
+                    String::from("[")
+                        + &patches
+                            .iter()
+                            .map(|patch| patch.to_js())
+                            .collect::<Vec<String>>()
+                            .join(",")
+                        + "]",
+                ]
+                .into_iter(),
+            ),
+            Self::ComputeWithIdData { id, func: _ } => runtime_call("textContent", &[id]),
+            Self::ComputeInputWithIdData { id, func: _ } => runtime_call("inputValue", &[id]),
+            Self::InsertAdjacent {
+                target,
+                position,
+                content,
+            } => target_call("insertAdjacent", target, &[position.as_str(), content]),
+            Self::RemoveNode { target } => target_call("remove", target, &[]),
+            Self::SetAttribute {
+                target,
+                name,
+                value,
+            } => target_call("setAttribute", target, &[name, value]),
+            Self::RemoveAttribute { target, name } => {
+                target_call("removeAttribute", target, &[name])
+            }
+            Self::AddClass { target, class } => target_call("addClass", target, &[class]),
+            Self::RemoveClass { target, class } => target_call("removeClass", target, &[class]),
+            Self::ToggleClass { target, class } => target_call("toggleClass", target, &[class]),
+            Self::SetStyle {
+                target,
+                property,
+                value,
+            } => target_call("setStyle", target, &[property, value]),
+            Self::SetValue { target, value } => target_call("setValue", target, &[value]),
+            Self::SetText { target, text } => target_call("setText", target, &[text]),
+            Self::SetBinding { field, value } => runtime_call("setBinding", &[field, value]),
+        };
+
+        Some(expression)
+    }
+
+    /// Runs the operations of a [ModifyView::Batch] in a single synchronous script
+    /// so the webview cannot render in between them, returning the results
+    /// of the compute operations in the order they were added
+    fn batch_expression(views: &[ModifyView]) -> String {
+        let mut expression = String::from("(function(){const results=[];");
+
+        for view in ModifyView::flatten(views) {
+            if let Some(operation) = view.expression() {
+                if view.callback().is_some() {
+                    expression = expression + "results.push(" + &operation + ");";
+                } else {
+                    expression = expression + &operation + ";";
+                }
+            }
+        }
+
+        expression + "return results;})()"
+    }
+
+    /// Take the theme, title and locale operations out of a [ModifyView::Batch] since
+    /// they also change the app, like the title of the window. Returns the batch without
+    /// them and the operations in the order they were added
+    pub(crate) fn take_app_operations(self) -> (Self, Vec<ModifyView>) {
+        let Self::Batch(views) = self else {
+            return (self, Vec::default());
+        };
+
+        let (app_operations, views) =
+            views
+                .into_iter()
+                .flat_map(ModifyView::into_flat)
+                .partition(|view| {
+                    matches!(
+                        view,
+                        Self::SetTheme(_) | Self::SetTitle(_) | Self::SetLocale(_)
+                    )
+                });
+
+        (Self::Batch(views), app_operations)
+    }
+
+    /// The owned operations of nested batches in the order they are performed
+    fn into_flat(self) -> Vec<ModifyView> {
+        match self {
+            Self::Batch(inner) => inner.into_iter().flat_map(ModifyView::into_flat).collect(),
+            _ => vec![self],
+        }
+    }
+
+    /// The operations of nested batches in the order they are performed
+    fn flatten(views: &[ModifyView]) -> Vec<&ModifyView> {
+        views
+            .iter()
+            .flat_map(|view| match view {
+                Self::Batch(inner) => ModifyView::flatten(inner),
+                _ => vec![view],
+            })
+            .collect()
+    }
+
+    fn callback(&self) -> Option<JsCallback> {
+        match self {
+            Self::ComputeWithIdData { func, .. } | Self::ComputeInputWithIdData { func, .. } => {
+                Some(*func)
+            }
+            _ => None,
+        }
+    }
+
+    /// The callbacks of the compute operations in a [ModifyView::Batch]
+    /// in the same order as the results returned by the batch script
+    pub fn batch_callbacks(&self) -> Vec<JsCallback> {
+        match self {
+            Self::Batch(views) => ModifyView::flatten(views)
+                .into_iter()
+                .filter_map(|view| view.callback())
+                .collect(),
+            _ => Vec::default(),
+        }
+    }
 }
 
 impl UiPaint for ModifyView {
     fn to_html(&self) -> Cow<str> {
         match self {
-            Self::ReplaceApp(content) => {
-                Cow::Borrowed(r#"document.getElementById("puppeteer_app").innerHTML=`"#)
-                    + content.as_ref()
-                    + "`;"
-            }
-            Self::ReplaceNodeWithId { id, content } => {
-                Cow::Borrowed(r#"document.getElementById(""#)
-                    + Cow::Owned(id.to_owned())
-                    + r#"").innerHTML=`"#
-                    + content.as_ref()
-                    + "`;"
-            }
             Self::CloseWindow => Cow::Borrowed("Close Window Requested"),
             Self::MaximizeWindow => Cow::Borrowed("Maximize Window Requested"),
             Self::MinimizeWindow => Cow::Borrowed("Minimize Window Requested"),
             Self::DragWindow => Cow::Borrowed("Dragging Window..."),
             Self::Skip => Cow::Borrowed("Skipped"),
-            Self::ComputeWithIdData { id, func: _ } => {
-                Cow::Borrowed("document.getElementById('")
-                    + Cow::Owned(id.to_owned())
-                    + "').textContent;"
-            }
-            Self::ComputeInputWithIdData { id, func: _ } => {
-                Cow::Borrowed("document.getElementById('") + Cow::Owned(id.to_owned()) + "').value;"
-            }
+            Self::Navigate(_) | Self::Back | Self::Forward => Cow::Borrowed("Navigating..."),
+            Self::SetLocale(_) => Cow::Borrowed("Changing the locale..."),
+            Self::SetTheme(Theme::System) => Cow::Borrowed("Following the system theme..."),
+            _ => Cow::Owned(self.expression().unwrap_or_default() + ";"),
         }
     }
 }
@@ -202,3 +768,217 @@ impl WindowResize {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod modify_view_checks {
+    use super::*;
+
+    const HOSTILE_INPUTS: [&str; 7] = [
+        "`; alert(1); `",
+        "${alert(document.cookie)}",
+        "\"); alert(1); (\"",
+        "'); alert(1); ('",
+        "</script><script>alert(1)</script>",
+        "\\\"\n\r\t\u{2028}\u{2029}\u{0}",
+        "<img src=x onerror=\"alert(1)\">",
+    ];
+
+    /// Decodes a JSON string literal to check that the encoded value is not altered
+    fn decode(literal: &str) -> String {
+        let mut chars = literal
+            .strip_prefix('"')
+            .and_then(|inner| inner.strip_suffix('"'))
+            .expect("A JSON string literal is wrapped in double quotes")
+            .chars();
+        let mut outcome = String::new();
+
+        while let Some(character) = chars.next() {
+            assert!(character != '"', "Unescaped double quote in `{literal}`");
+            assert!(character != '<', "Unescaped `<` in `{literal}`");
+            assert!((character as u32) >= 0x20, "Unescaped control character");
+
+            if character != '\\' {
+                outcome.push(character);
+                continue;
+            }
+
+            match chars.next().unwrap() {
+                'n' => outcome.push('\n'),
+                'r' => outcome.push('\r'),
+                't' => outcome.push('\t'),
+                'u' => {
+                    let code = chars.by_ref().take(4).collect::<String>();
+                    outcome.push(char::from_u32(u32::from_str_radix(&code, 16).unwrap()).unwrap());
+                }
+                escaped => outcome.push(escaped),
+            }
+        }
+
+        outcome
+    }
+
+    #[test]
+    fn escapes_hostile_content() {
+        HOSTILE_INPUTS.iter().for_each(|input| {
+            let script = ModifyView::ReplaceApp(input.to_string())
+                .to_html()
+                .to_string();
+            let literal = script
+                .strip_prefix("window.puppeteer.replaceApp(")
+                .and_then(|script| script.strip_suffix(");"))
+                .unwrap();
+            assert_eq!(*input, decode(literal));
+
+            let script = ModifyView::ReplaceNodeWithId {
+                id: input.to_string(),
+                content: input.to_string(),
+            }
+            .to_html()
+            .to_string();
+            let literals = script
+                .strip_prefix("window.puppeteer.replaceNode(")
+                .and_then(|script| script.strip_suffix(");"))
+                .unwrap();
+            let encoded = js_string(input);
+            assert_eq!(String::new() + &encoded + "," + &encoded, literals);
+            assert_eq!(*input, decode(&encoded));
+
+            let script = ModifyView::compute_with_data(input, |_| ModifyView::Skip)
+                .to_html()
+                .to_string();
+            assert_eq!(
+                String::from("window.puppeteer.textContent(") + &encoded + ");",
+                script
+            );
+
+            let script = ModifyView::compute_input_with_data(input, |_| ModifyView::Skip)
+                .to_html()
+                .to_string();
+            assert_eq!(
+                String::from("window.puppeteer.inputValue(") + &encoded + ");",
+                script
+            );
+        });
+    }
+
+    #[test]
+    fn mutations() {
+        let hostile = HOSTILE_INPUTS[0];
+        let encoded = js_string(hostile);
+
+        assert_eq!(
+            String::from(r#"window.puppeteer.insertAdjacent({id:"list"},"beforeend","#)
+                + &encoded
+                + ");",
+            ModifyView::append(Target::id("list"), hostile).to_html()
+        );
+        assert_eq!(
+            String::from(
+                r#"window.puppeteer.insertAdjacent({selector:"ul \u003e li"},"afterbegin","#
+            ) + &encoded
+                + ");",
+            ModifyView::prepend(Target::selector("ul > li"), hostile).to_html()
+        );
+        assert_eq!(
+            String::from(r#"window.puppeteer.remove({selector:"#) + &encoded + "});",
+            ModifyView::remove_node(Target::selector(hostile)).to_html()
+        );
+        assert_eq!(
+            String::from(r#"window.puppeteer.setAttribute({id:"user_email"},"placeholder","#)
+                + &encoded
+                + ");",
+            ModifyView::set_attribute(Target::id("user_email"), "placeholder", hostile).to_html()
+        );
+        assert_eq!(
+            r#"window.puppeteer.removeAttribute({id:"submit"},"disabled");"#,
+            ModifyView::remove_attribute(Target::id("submit"), "disabled").to_html()
+        );
+        assert_eq!(
+            r#"window.puppeteer.toggleClass({selector:".item"},"active");"#,
+            ModifyView::toggle_class(Target::selector(".item"), "active").to_html()
+        );
+        assert_eq!(
+            r#"window.puppeteer.setStyle({id:"inc"},"--pp-primary","#.to_owned() + &encoded + ");",
+            ModifyView::set_style(Target::id("inc"), "--pp-primary", hostile).to_html()
+        );
+        assert_eq!(
+            String::from(r#"window.puppeteer.setValue({id:"user_email"},"#) + &encoded + ");",
+            ModifyView::set_value(Target::id("user_email"), hostile).to_html()
+        );
+        assert_eq!(
+            String::from(r#"window.puppeteer.setText({id:"inc"},"#) + &encoded + ");",
+            ModifyView::set_text(Target::id("inc"), hostile).to_html()
+        );
+    }
+
+    #[test]
+    fn batch() {
+        fn callback(value: &str) -> ModifyView {
+            ModifyView::set_text(Target::id("outcome"), value)
+        }
+
+        let batch = ModifyView::set_text(Target::id("status"), "saving")
+            .then(ModifyView::compute_input_with_data("email", callback))
+            .then(ModifyView::batch([
+                ModifyView::Skip,
+                ModifyView::add_class(Target::selector(".form"), "saved"),
+                ModifyView::compute_with_data("count", callback),
+            ]));
+
+        assert_eq!(
+            concat!(
+                r#"(function(){const results=[];window.puppeteer.setText({id:"status"},"saving");"#,
+                r#"results.push(window.puppeteer.inputValue("email"));"#,
+                r#"window.puppeteer.addClass({selector:".form"},"saved");"#,
+                r#"results.push(window.puppeteer.textContent("count"));return results;})();"#
+            ),
+            batch.to_html()
+        );
+        assert_eq!(2, batch.batch_callbacks().len());
+
+        let (batch, app_operations) = ModifyView::SetTheme(Theme::Dark)
+            .then(ModifyView::set_text(Target::id("status"), "saved"))
+            .then(ModifyView::batch([
+                ModifyView::SetTitle("Saved".to_owned()),
+                ModifyView::SetTheme(Theme::System),
+                ModifyView::SetLocale("fr".to_owned()),
+            ]))
+            .take_app_operations();
+        assert_eq!(
+            ModifyView::batch([ModifyView::set_text(Target::id("status"), "saved")]),
+            batch
+        );
+        assert_eq!(
+            vec![
+                ModifyView::SetTheme(Theme::Dark),
+                ModifyView::SetTitle("Saved".to_owned()),
+                ModifyView::SetTheme(Theme::System),
+                ModifyView::SetLocale("fr".to_owned()),
+            ],
+            app_operations
+        );
+
+        assert_eq!(
+            Some(vec![r#""a,\"]""#, "null", r#"{"b":[1,2]}"#]),
+            crate::utils::split_json_array(r#"["a,\"]", null , {"b":[1,2]}]"#)
+        );
+        assert_eq!(Some(vec![]), crate::utils::split_json_array("[]"));
+        assert_eq!(None, crate::utils::split_json_array("null"));
+    }
+
+    #[test]
+    fn encodes_known_values() {
+        assert_eq!(
+            r#"window.puppeteer.replaceApp("`${alert(1)}`");"#,
+            ModifyView::ReplaceApp("`${alert(1)}`".to_owned()).to_html()
+        );
+        assert_eq!(
+            r#"window.puppeteer.replaceNode("a\"b","\u003c/script\u003e\u2028");"#,
+            ModifyView::ReplaceNodeWithId {
+                id: "a\"b".to_owned(),
+                content: "</script>\u{2028}".to_owned(),
+            }
+            .to_html()
+        );
+    }
+}
@@ -119,3 +119,75 @@ macro_rules! load_cow {
         outcome
     }};
 }
+
+/// Encode a string as a JavaScript string literal (the JSON string format) so that it can be
+/// passed safely into scripts evaluated by the webview
+pub(crate) fn js_string(value: &str) -> String {
+    let mut outcome = String::with_capacity(value.len() + 2);
+    outcome.push('"');
+
+    value.chars().for_each(|character| match character {
+        '"' => outcome.push_str("\\\""),
+        '\\' => outcome.push_str("\\\\"),
+        '\n' => outcome.push_str("\\n"),
+        '\r' => outcome.push_str("\\r"),
+        '\t' => outcome.push_str("\\t"),
+        // Prevents a `</script>` in the value from closing an enclosing script element
+        '<' => outcome.push_str("\\u003c"),
+        '>' => outcome.push_str("\\u003e"),
+        // Line and paragraph separators are not valid in older JavaScript string literals
+        '\u{2028}' => outcome.push_str("\\u2028"),
+        '\u{2029}' => outcome.push_str("\\u2029"),
+        control if (control as u32) < 0x20 => {
+            outcome.push_str(&format!("\\u{:04x}", control as u32))
+        }
+        _ => outcome.push(character),
+    });
+
+    outcome.push('"');
+
+    outcome
+}
+
+/// Splits a JSON array into the JSON text of its elements without decoding them,
+/// returning `None` if the value is not an array
+pub(crate) fn split_json_array(value: &str) -> Option<Vec<&str>> {
+    let inner = value.trim().strip_prefix('[')?.strip_suffix(']')?;
+    let mut elements = Vec::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut start = 0usize;
+
+    for (index, character) in inner.char_indices() {
+        if in_string {
+            match (escaped, character) {
+                (true, _) => escaped = false,
+                (false, '\\') => escaped = true,
+                (false, '"') => in_string = false,
+                _ => (),
+            }
+
+            continue;
+        }
+
+        match character {
+            '"' => in_string = true,
+            '[' | '{' => depth += 1,
+            ']' | '}' => depth = depth.checked_sub(1)?,
+            ',' if depth == 0 => {
+                elements.push(inner[start..index].trim());
+                start = index + 1;
+            }
+            _ => (),
+        }
+    }
+
+    let last = inner[start..].trim();
+
+    if !last.is_empty() || !elements.is_empty() {
+        elements.push(last);
+    }
+
+    Some(elements)
+}
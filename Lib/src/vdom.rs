@@ -0,0 +1,654 @@
+use crate::{escape_html, utils::js_string, ModifyView, UiPaint};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+
+/// Elements that cannot have children and are rendered without a closing tag
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// A node of a virtual DOM tree.
+/// The tree renders to HTML using [UiPaint] and is diffed against the previously
+/// rendered tree of the same mount point using [VDom::render].
+///
+/// Children with a key are matched by their key when diffing so that reordering
+/// a keyed list moves the existing nodes instead of rebuilding them.
+/// The HTML parser must produce the same nodes as the tree so elements the parser
+/// restructures, like a `<tr>` without a `<tbody>` or a `<div>` inside a `<p>`, should be avoided.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub enum VNode {
+    /// An element like `<div>`
+    Element(VElement),
+    /// A text node. The text is escaped when rendered
+    Text(String),
+    /// HTML rendered as is without escaping. When diffed it is replaced as a whole
+    /// if it changes so it should contain a single element
+    Raw(String),
+}
+
+/// An element of a [VNode] tree
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct VElement {
+    /// The name of the tag like `div`
+    pub tag: String,
+    /// The key used to match the element with the previous tree. It is not rendered
+    pub key: Option<String>,
+    /// The attributes of the element
+    pub attributes: BTreeMap<String, String>,
+    /// The child nodes of the element
+    pub children: Vec<VNode>,
+}
+
+impl VNode {
+    /// Create an element without attributes or children
+    pub fn element(tag: &str) -> Self {
+        VNode::Element(VElement {
+            tag: tag.to_lowercase(),
+            key: Option::None,
+            attributes: BTreeMap::default(),
+            children: Vec::default(),
+        })
+    }
+
+    /// Create a text node
+    pub fn text(text: &str) -> Self {
+        VNode::Text(text.to_owned())
+    }
+
+    /// Create a node from HTML that is rendered without escaping.
+    /// Never use this with content that is not trusted
+    pub fn raw(html: &str) -> Self {
+        VNode::Raw(html.to_owned())
+    }
+
+    /// Set the key of an element. This has no effect on text nodes
+    pub fn key(mut self, key: &str) -> Self {
+        if let VNode::Element(element) = &mut self {
+            element.key.replace(key.to_owned());
+        }
+
+        self
+    }
+
+    /// Add or replace an attribute of an element. This has no effect on text nodes.
+    /// Names that are not valid attribute names, like names containing spaces,
+    /// quotes, `=`, `/` or `>`, are ignored since they would break the markup
+    pub fn attr(mut self, name: &str, value: &str) -> Self {
+        if !is_valid_attribute_name(name) {
+            return self;
+        }
+
+        if let VNode::Element(element) = &mut self {
+            element
+                .attributes
+                .insert(name.to_lowercase(), value.to_owned());
+        }
+
+        self
+    }
+
+    /// Add a child node to an element. This has no effect on text nodes
+    /// and void elements like `<input>` which cannot have children
+    pub fn child(mut self, child: VNode) -> Self {
+        if let Some(element) = self.parent_element() {
+            element.children.push(child);
+        }
+
+        self
+    }
+
+    /// Add child nodes to an element. This has no effect on text nodes
+    /// and void elements like `<input>` which cannot have children
+    pub fn children(mut self, children: impl IntoIterator<Item = VNode>) -> Self {
+        if let Some(element) = self.parent_element() {
+            element.children.extend(children);
+        }
+
+        self
+    }
+
+    fn parent_element(&mut self) -> Option<&mut VElement> {
+        match self {
+            VNode::Element(element) if !VOID_ELEMENTS.contains(&element.tag.as_str()) => {
+                Some(element)
+            }
+            _ => None,
+        }
+    }
+
+    /// Merge adjacent text nodes and remove empty text nodes since the HTML parser
+    /// would create a single text node or none at all for them
+    pub fn normalize(self) -> Self {
+        match self {
+            VNode::Text(_) | VNode::Raw(_) => self,
+            VNode::Element(mut element) => {
+                let mut children = Vec::<VNode>::with_capacity(element.children.len());
+
+                for child in element.children.into_iter().map(VNode::normalize) {
+                    match (children.last_mut(), child) {
+                        (_, VNode::Text(text)) if text.is_empty() => (),
+                        (Some(VNode::Text(previous)), VNode::Text(text)) => {
+                            previous.push_str(&text)
+                        }
+                        (_, child) => children.push(child),
+                    }
+                }
+
+                element.children = children;
+
+                VNode::Element(element)
+            }
+        }
+    }
+
+    fn write_html(&self, outcome: &mut String) {
+        match self {
+            VNode::Text(text) => outcome.push_str(&escape_html(text)),
+            VNode::Raw(html) => outcome.push_str(html),
+            VNode::Element(element) => {
+                outcome.push('<');
+                outcome.push_str(&element.tag);
+
+                for (name, value) in element.attributes.iter() {
+                    outcome.push(' ');
+                    outcome.push_str(name);
+                    outcome.push_str("=\"");
+                    outcome.push_str(&escape_html(value));
+                    outcome.push('"');
+                }
+
+                outcome.push('>');
+
+                if VOID_ELEMENTS.contains(&element.tag.as_str()) {
+                    return;
+                }
+
+                element
+                    .children
+                    .iter()
+                    .for_each(|child| child.write_html(outcome));
+
+                outcome.push_str("</");
+                outcome.push_str(&element.tag);
+                outcome.push('>');
+            }
+        }
+    }
+}
+
+impl UiPaint for VNode {
+    fn to_html(&self) -> Cow<str> {
+        let mut outcome = String::new();
+        self.write_html(&mut outcome);
+
+        Cow::Owned(outcome)
+    }
+}
+
+/// A DOM operation produced by diffing two [VNode] trees.
+/// A path is the list of child indexes leading to a node starting from
+/// the root of the tree, so an empty path is the root of the tree.
+/// Patches are applied in order and each path refers to the DOM
+/// as modified by the previous patches.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub enum DomPatch {
+    /// Replace the node at the path
+    Replace {
+        /// The path of the node
+        path: Vec<usize>,
+        /// The new node
+        node: VNode,
+    },
+    /// Change the text of the text node at the path
+    SetText {
+        /// The path of the text node
+        path: Vec<usize>,
+        /// The new text
+        text: String,
+    },
+    /// Add or replace an attribute of the element at the path
+    SetAttribute {
+        /// The path of the element
+        path: Vec<usize>,
+        /// The name of the attribute
+        name: String,
+        /// The value of the attribute
+        value: String,
+    },
+    /// Remove an attribute of the element at the path
+    RemoveAttribute {
+        /// The path of the element
+        path: Vec<usize>,
+        /// The name of the attribute
+        name: String,
+    },
+    /// Insert a child in the element at the path
+    Insert {
+        /// The path of the parent element
+        path: Vec<usize>,
+        /// The index of the new child
+        index: usize,
+        /// The new child
+        node: VNode,
+    },
+    /// Remove the node at the path
+    Remove {
+        /// The path of the node
+        path: Vec<usize>,
+    },
+    /// Move a child of the element at the path to a lower index
+    Move {
+        /// The path of the parent element
+        path: Vec<usize>,
+        /// The current index of the child
+        from: usize,
+        /// The index of the child after moving it
+        to: usize,
+    },
+}
+
+impl DomPatch {
+    /// The JavaScript object used by the `patch` function of [crate::PUPPETEER_RUNTIME_SCRIPT]
+    pub(crate) fn to_js(&self) -> String {
+        let path = |path: &[usize]| {
+            String::from("[")
+                + &path
+                    .iter()
+                    .map(|index| index.to_string())
+                    .collect::<Vec<String>>()
+                    .join(",")
+                + "]"
+        };
+
+        match self {
+            DomPatch::Replace { path: at, node } => {
+                String::from("{op:\"replace\",path:")
+                    + &path(at)
+                    + ",html:"
+                    + &js_string(&node.to_html())
+                    + "}"
+            }
+            DomPatch::SetText { path: at, text } => {
+                String::from("{op:\"text\",path:") + &path(at) + ",text:" + &js_string(text) + "}"
+            }
+            DomPatch::SetAttribute {
+                path: at,
+                name,
+                value,
+            } => {
+                String::from("{op:\"attr\",path:")
+                    + &path(at)
+                    + ",name:"
+                    + &js_string(name)
+                    + ",value:"
+                    + &js_string(value)
+                    + "}"
+            }
+            DomPatch::RemoveAttribute { path: at, name } => {
+                String::from("{op:\"rmattr\",path:") + &path(at) + ",name:" + &js_string(name) + "}"
+            }
+            DomPatch::Insert {
+                path: at,
+                index,
+                node,
+            } => {
+                String::from("{op:\"insert\",path:")
+                    + &path(at)
+                    + ",index:"
+                    + &index.to_string()
+                    + ",html:"
+                    + &js_string(&node.to_html())
+                    + "}"
+            }
+            DomPatch::Remove { path: at } => String::from("{op:\"remove\",path:") + &path(at) + "}",
+            DomPatch::Move { path: at, from, to } => {
+                String::from("{op:\"move\",path:")
+                    + &path(at)
+                    + ",from:"
+                    + &from.to_string()
+                    + ",to:"
+                    + &to.to_string()
+                    + "}"
+            }
+        }
+    }
+}
+
+/// Compute the patches that turn the `old` tree into the `new` tree.
+/// Both trees should be normalized using [VNode::normalize]
+pub fn diff(old: &VNode, new: &VNode) -> Vec<DomPatch> {
+    let mut patches = Vec::new();
+    diff_node(old, new, &mut Vec::new(), &mut patches);
+
+    patches
+}
+
+fn diff_node(old: &VNode, new: &VNode, path: &mut Vec<usize>, patches: &mut Vec<DomPatch>) {
+    match (old, new) {
+        (VNode::Text(old_text), VNode::Text(new_text)) => {
+            if old_text != new_text {
+                patches.push(DomPatch::SetText {
+                    path: path.clone(),
+                    text: new_text.clone(),
+                });
+            }
+        }
+        (VNode::Raw(old_html), VNode::Raw(new_html)) if old_html == new_html => (),
+        (VNode::Element(old_element), VNode::Element(new_element))
+            if old_element.tag == new_element.tag && old_element.key == new_element.key =>
+        {
+            for name in old_element.attributes.keys() {
+                if !new_element.attributes.contains_key(name) {
+                    patches.push(DomPatch::RemoveAttribute {
+                        path: path.clone(),
+                        name: name.clone(),
+                    });
+                }
+            }
+
+            for (name, value) in new_element.attributes.iter() {
+                if old_element.attributes.get(name) != Some(value) {
+                    patches.push(DomPatch::SetAttribute {
+                        path: path.clone(),
+                        name: name.clone(),
+                        value: value.clone(),
+                    });
+                }
+            }
+
+            diff_children(&old_element.children, &new_element.children, path, patches);
+        }
+        _ => patches.push(DomPatch::Replace {
+            path: path.clone(),
+            node: new.clone(),
+        }),
+    }
+}
+
+fn node_key(node: &VNode) -> Option<&str> {
+    match node {
+        VNode::Element(element) => element.key.as_deref(),
+        VNode::Text(_) | VNode::Raw(_) => Option::None,
+    }
+}
+
+fn is_valid_attribute_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().all(|character| {
+            !character.is_whitespace()
+                && !character.is_control()
+                && !matches!(character, '"' | '\'' | '>' | '/' | '=' | '<')
+        })
+}
+
+fn diff_children(old: &[VNode], new: &[VNode], path: &mut Vec<usize>, patches: &mut Vec<DomPatch>) {
+    // Keyed children are matched by key and the others are matched in order
+    let mut keyed = HashMap::<&str, usize>::new();
+    let mut unkeyed = Vec::<usize>::new();
+
+    for (index, node) in old.iter().enumerate() {
+        match node_key(node) {
+            Some(key) => {
+                keyed.insert(key, index);
+            }
+            None => unkeyed.push(index),
+        }
+    }
+
+    let mut unkeyed = unkeyed.into_iter();
+    let matches = new
+        .iter()
+        .map(|node| match node_key(node) {
+            Some(key) => keyed.remove(key),
+            None => unkeyed.next(),
+        })
+        .collect::<Vec<Option<usize>>>();
+
+    // The old indexes of the children in their current order in the DOM
+    let mut current = (0..old.len()).collect::<Vec<usize>>();
+
+    for index in (0..old.len()).rev() {
+        if !matches.contains(&Some(index)) {
+            current.remove(index);
+            patches.push(DomPatch::Remove {
+                path: child_path(path, index),
+            });
+        }
+    }
+
+    for (index, (node, matched)) in new.iter().zip(matches.iter()).enumerate() {
+        match matched {
+            Some(old_index) => {
+                let position = current
+                    .iter()
+                    .position(|current_index| current_index == old_index)
+                    .unwrap_or(index);
+
+                if position != index {
+                    current.remove(position);
+                    current.insert(index, *old_index);
+                    patches.push(DomPatch::Move {
+                        path: path.clone(),
+                        from: position,
+                        to: index,
+                    });
+                }
+
+                path.push(index);
+                diff_node(&old[*old_index], node, path, patches);
+                path.pop();
+            }
+            None => {
+                current.insert(index, usize::MAX);
+                patches.push(DomPatch::Insert {
+                    path: path.clone(),
+                    index,
+                    node: node.clone(),
+                });
+            }
+        }
+    }
+}
+
+fn child_path(path: &[usize], index: usize) -> Vec<usize> {
+    let mut outcome = path.to_vec();
+    outcome.push(index);
+
+    outcome
+}
+
+/// Remembers the last [VNode] tree rendered in each mount point.
+/// A mount point is an element with an `id` whose content is managed by the tree.
+/// This can be cloned and shared across the event handlers.
+#[derive(Debug, Default, Clone)]
+pub struct VDom {
+    trees: Arc<Mutex<HashMap<String, VNode>>>,
+}
+
+impl VDom {
+    /// Create a registry without any mounted tree
+    pub fn new() -> Self {
+        VDom::default()
+    }
+
+    /// Render `tree` in the mount point with the `id`.
+    /// The first render replaces the content of the mount point and the following renders
+    /// only apply the patches between the previous tree and `tree`.
+    /// Returns [ModifyView::Skip] if nothing changed
+    pub fn render(&self, id: &str, tree: VNode) -> ModifyView {
+        let tree = tree.normalize();
+        let mut trees = self
+            .trees
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let outcome = match trees.get(id) {
+            Some(previous) => {
+                let patches = diff(previous, &tree);
+
+                if patches.is_empty() {
+                    ModifyView::Skip
+                } else {
+                    ModifyView::Patch {
+                        id: id.to_owned(),
+                        patches,
+                    }
+                }
+            }
+            None => ModifyView::ReplaceNodeWithId {
+                id: id.to_owned(),
+                content: tree.to_html().to_string(),
+            },
+        };
+
+        trees.insert(id.to_owned(), tree);
+
+        outcome
+    }
+
+    /// Forget the tree of a mount point so that the next render replaces its content.
+    /// This is needed when the content of the mount point is replaced without using [VDom::render]
+    pub fn forget(&self, id: &str) {
+        self.trees
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(id);
+    }
+}
+
+#[cfg(test)]
+mod vdom_checks {
+    use super::*;
+
+    fn node_at<'a>(root: &'a mut VNode, path: &[usize]) -> &'a mut VNode {
+        path.iter().fold(root, |node, index| match node {
+            VNode::Element(element) => &mut element.children[*index],
+            _ => panic!("Only elements have children"),
+        })
+    }
+
+    fn children_at<'a>(root: &'a mut VNode, path: &[usize]) -> &'a mut Vec<VNode> {
+        match node_at(root, path) {
+            VNode::Element(element) => &mut element.children,
+            _ => panic!("Only elements have children"),
+        }
+    }
+
+    /// Applies the patches the same way the runtime applies them to the DOM
+    fn apply(mut tree: VNode, patches: &[DomPatch]) -> VNode {
+        for patch in patches {
+            match patch.clone() {
+                DomPatch::Replace { path, node } => *node_at(&mut tree, &path) = node,
+                DomPatch::SetText { path, text } => *node_at(&mut tree, &path) = VNode::Text(text),
+                DomPatch::SetAttribute { path, name, value } => {
+                    let node = node_at(&mut tree, &path).clone().attr(&name, &value);
+                    *node_at(&mut tree, &path) = node;
+                }
+                DomPatch::RemoveAttribute { path, name } => {
+                    if let VNode::Element(element) = node_at(&mut tree, &path) {
+                        element.attributes.remove(&name);
+                    }
+                }
+                DomPatch::Insert { path, index, node } => {
+                    children_at(&mut tree, &path).insert(index, node)
+                }
+                DomPatch::Remove { mut path } => {
+                    let index = path.pop().unwrap();
+                    children_at(&mut tree, &path).remove(index);
+                }
+                DomPatch::Move { path, from, to } => {
+                    let children = children_at(&mut tree, &path);
+                    let node = children.remove(from);
+                    children.insert(to, node);
+                }
+            }
+        }
+
+        tree
+    }
+
+    fn list(keys: &[&str]) -> VNode {
+        VNode::element("ul").children(
+            keys.iter()
+                .map(|key| VNode::element("li").key(key).child(VNode::text(key))),
+        )
+    }
+
+    #[test]
+    fn keyed_diff() {
+        let old = list(&["a", "b", "c", "d", "e"]);
+        let new = list(&["e", "c", "f", "a"]);
+        let patches = diff(&old, &new);
+
+        assert_eq!(new, apply(old.clone(), &patches));
+        assert_eq!(
+            3,
+            patches
+                .iter()
+                .filter(|patch| matches!(patch, DomPatch::Remove { .. }))
+                .count()
+                + patches
+                    .iter()
+                    .filter(|patch| matches!(patch, DomPatch::Insert { .. }))
+                    .count()
+        );
+        assert!(!patches
+            .iter()
+            .any(|patch| matches!(patch, DomPatch::Replace { .. })));
+
+        let old = VNode::element("div")
+            .attr("class", "a")
+            .attr("hidden", "")
+            .child(VNode::text("Hello "))
+            .child(VNode::text("World"))
+            .child(VNode::text(""));
+        let new = VNode::element("div")
+            .attr("class", "b")
+            .child(VNode::text("Hello Puppeteer"))
+            .child(VNode::element("input").attr("value", "<\"&>"));
+        let patches = diff(&old.clone().normalize(), &new);
+
+        assert_eq!(new, apply(old.normalize(), &patches));
+        assert_eq!(
+            r#"<div class="b">Hello Puppeteer<input value="&lt;&quot;&amp;&gt;"></div>"#,
+            new.to_html()
+        );
+    }
+
+    #[test]
+    fn mount_points() {
+        let vdom = VDom::new();
+
+        assert!(matches!(
+            vdom.render("app", list(&["a"])),
+            ModifyView::ReplaceNodeWithId { .. }
+        ));
+        assert_eq!(ModifyView::Skip, vdom.render("app", list(&["a"])));
+        assert_eq!(
+            ModifyView::Patch {
+                id: "app".to_owned(),
+                patches: vec![DomPatch::Insert {
+                    path: vec![],
+                    index: 1,
+                    node: VNode::element("li").key("b").child(VNode::text("b")),
+                }],
+            },
+            vdom.render("app", list(&["a", "b"]))
+        );
+        assert_eq!(
+            r#"window.puppeteer.patch("app",[{op:"remove",path:[1]}]);"#,
+            vdom.render("app", list(&["a"])).to_html()
+        );
+
+        vdom.forget("app");
+
+        assert!(matches!(
+            vdom.render("app", list(&["a"])),
+            ModifyView::ReplaceNodeWithId { .. }
+        ));
+    }
+}
@@ -0,0 +1,290 @@
+use crate::{ActiveAppEnv, ModifyView, PuppeteerError, PuppeteerResult, UiPaint};
+use async_trait::async_trait;
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    ops::Range,
+    sync::{Arc, Mutex},
+};
+
+/// The prefix of the IPC messages sent when a [VirtualList] needs rows.
+/// The full message is `puppeteer:vlist>{id}>{start}>{end}`
+pub const VIRTUAL_LIST_IPC_PREFIX: &str = "puppeteer:vlist>";
+
+/// The maximum number of rows sent for one request so that a huge viewport
+/// or a forged request cannot freeze the webview
+pub const MAX_VIRTUAL_LIST_ROWS: usize = 500;
+
+/// The default number of rows rendered above and below the visible rows
+pub const DEFAULT_VIRTUAL_LIST_OVERSCAN: usize = 10;
+
+/// Supplies the rows of a [VirtualList] one page at a time
+#[async_trait]
+pub trait ListDataSource: Send + Sync + 'static {
+    /// The type of the rows
+    type Row: UiPaint + Send;
+
+    /// The total number of rows
+    async fn len(&self, app_env: &ActiveAppEnv) -> usize;
+
+    /// The rows in the range. The range is never past [ListDataSource::len]
+    async fn rows(&self, range: Range<usize>, app_env: &ActiveAppEnv) -> Vec<Self::Row>;
+}
+
+#[async_trait]
+trait RegisteredSource: Send + Sync {
+    async fn len(&self, app_env: &ActiveAppEnv) -> usize;
+
+    async fn html(&self, range: Range<usize>, app_env: &ActiveAppEnv) -> String;
+}
+
+#[async_trait]
+impl<S: ListDataSource> RegisteredSource for S {
+    async fn len(&self, app_env: &ActiveAppEnv) -> usize {
+        ListDataSource::len(self, app_env).await
+    }
+
+    async fn html(&self, range: Range<usize>, app_env: &ActiveAppEnv) -> String {
+        ListDataSource::rows(self, range, app_env)
+            .await
+            .iter()
+            .map(|row| row.to_html())
+            .collect()
+    }
+}
+
+/// A scrollable list that only renders the visible rows.
+/// Every row must have the same height in pixels.
+///
+/// Paint the list into the view and register its [ListDataSource] using
+/// [VirtualLists::register]. When the list is added to the document or scrolled,
+/// the [crate::PUPPETEER_RUNTIME_SCRIPT] requests the visible rows through IPC.
+///
+/// #### Example
+/// ```rust
+/// use puppeteer::{async_trait, ActiveAppEnv, Html, ListDataSource, UiPaint, VNode, VirtualList};
+/// use std::ops::Range;
+///
+/// struct Numbers;
+///
+/// #[async_trait::async_trait]
+/// impl ListDataSource for Numbers {
+///     type Row = VNode;
+///
+///     async fn len(&self, _app_env: &ActiveAppEnv) -> usize {
+///         100_000
+///     }
+///
+///     async fn rows(&self, range: Range<usize>, _app_env: &ActiveAppEnv) -> Vec<VNode> {
+///         range
+///             .map(|index| Html::div().child(Html::text(&index.to_string())))
+///             .collect()
+///     }
+/// }
+///
+/// let app_env = ActiveAppEnv::new("virtual_list");
+/// app_env.virtual_lists.register("numbers", Numbers);
+/// let list = VirtualList::new("numbers", 24).to_html();
+/// ```
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct VirtualList {
+    id: String,
+    row_height: u32,
+    overscan: usize,
+    height: String,
+}
+
+impl VirtualList {
+    /// Create a list with the `id` of its [ListDataSource] and the height of the rows in pixels
+    pub fn new(id: &str, row_height: u32) -> Self {
+        VirtualList {
+            id: id.to_owned(),
+            row_height: row_height.max(1),
+            overscan: DEFAULT_VIRTUAL_LIST_OVERSCAN,
+            height: String::from("100%"),
+        }
+    }
+
+    /// Change the number of rows rendered above and below the visible rows
+    pub fn overscan(mut self, rows: usize) -> Self {
+        self.overscan = rows;
+
+        self
+    }
+
+    /// Change the CSS height of the scrollable element. Defaults to `100%`
+    pub fn height(mut self, height: &str) -> Self {
+        self.height = height.to_owned();
+
+        self
+    }
+}
+
+impl UiPaint for VirtualList {
+    fn to_html(&self) -> Cow<str> {
+        let id = crate::escape_html(&self.id);
+
+        Cow::Owned(
+            String::from(r#"<div id=""#)
+                + &id
+                + r#"" class="pp-vlist" data-pp-vlist="" data-pp-row-height=""#
+                + &self.row_height.to_string()
+                + r#"" data-pp-overscan=""#
+                + &self.overscan.to_string()
+                + r#"" style="position: relative; overflow-y: auto; height: "#
+                + &crate::escape_html(&self.height)
+                + r#";"><div class="pp-vlist-spacer" style="height: 0px;"></div><div id=""#
+                + &id
+                + r#"-rows" class="pp-vlist-rows" style="position: absolute; top: 0; left: 0; right: 0;"></div></div>"#,
+        )
+    }
+}
+
+/// The rows requested by a [VirtualList]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ListRequest {
+    /// The `id` of the list
+    pub id: String,
+    /// The rows requested. The end is exclusive
+    pub range: Range<usize>,
+}
+
+impl ListRequest {
+    /// Returns `None` if the IPC message is not a valid list request
+    pub fn from_ipc(message: &str) -> Option<Self> {
+        let mut parts = message.strip_prefix(VIRTUAL_LIST_IPC_PREFIX)?.split('>');
+
+        let request = ListRequest {
+            id: parts.next()?.to_owned(),
+            range: parts.next()?.parse().ok()?..parts.next()?.parse().ok()?,
+        };
+
+        (parts.next().is_none() && request.range.start <= request.range.end).then_some(request)
+    }
+}
+
+/// The [ListDataSource] of each [VirtualList], shared by the event handlers
+#[derive(Default, Clone)]
+pub struct VirtualLists {
+    sources: Arc<Mutex<HashMap<String, Arc<dyn RegisteredSource>>>>,
+}
+
+impl VirtualLists {
+    /// Create the registry without any list
+    pub fn new() -> Self {
+        VirtualLists::default()
+    }
+
+    /// Register the data source of the list with the `id`, replacing the previous one
+    pub fn register(&self, id: &str, source: impl ListDataSource) {
+        self.lock().insert(id.to_owned(), Arc::new(source));
+    }
+
+    /// Remove the data source of the list
+    pub fn unregister(&self, id: &str) {
+        self.lock().remove(id);
+    }
+
+    /// Is a data source registered for the list
+    pub fn is_registered(&self, id: &str) -> bool {
+        self.lock().contains_key(id)
+    }
+
+    /// Load the requested rows, at most [MAX_VIRTUAL_LIST_ROWS], and return
+    /// the [ModifyView::VirtualRows] painting them
+    pub async fn rows(
+        &self,
+        request: ListRequest,
+        app_env: &ActiveAppEnv,
+    ) -> PuppeteerResult<ModifyView> {
+        let source = self
+            .lock()
+            .get(&request.id)
+            .cloned()
+            .ok_or_else(|| PuppeteerError::VirtualListNotRegistered(request.id.clone()))?;
+
+        let total = source.len(app_env).await;
+        let start = request.range.start.min(total);
+        let end = request
+            .range
+            .end
+            .min(total)
+            .min(start + MAX_VIRTUAL_LIST_ROWS);
+
+        Ok(ModifyView::VirtualRows {
+            id: request.id,
+            total,
+            start,
+            rows: source.html(start..end, app_env).await,
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Arc<dyn RegisteredSource>>> {
+        self.sources
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl core::fmt::Debug for VirtualLists {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("VirtualLists")
+            .field("registered", &self.lock().keys().collect::<Vec<&String>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod virtual_list_checks {
+    use super::*;
+    use crate::{Html, VNode};
+
+    struct Numbers;
+
+    #[async_trait]
+    impl ListDataSource for Numbers {
+        type Row = VNode;
+
+        async fn len(&self, _app_env: &ActiveAppEnv) -> usize {
+            10_000
+        }
+
+        async fn rows(&self, range: Range<usize>, _app_env: &ActiveAppEnv) -> Vec<VNode> {
+            range
+                .map(|index| Html::p().child(Html::text(&index.to_string())))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn pages() {
+        let app_env = ActiveAppEnv::new("virtual_list_checks");
+        app_env.virtual_lists.register("numbers", Numbers);
+
+        let request = ListRequest::from_ipc("puppeteer:vlist>numbers>9998>10040").unwrap();
+        assert_eq!(
+            ModifyView::VirtualRows {
+                id: "numbers".to_owned(),
+                total: 10_000,
+                start: 9998,
+                rows: "<p>9998</p><p>9999</p>".to_owned(),
+            },
+            smol::block_on(app_env.virtual_lists.rows(request, &app_env)).unwrap()
+        );
+
+        let request = ListRequest::from_ipc("puppeteer:vlist>numbers>0>100000").unwrap();
+        let Ok(ModifyView::VirtualRows { rows, .. }) =
+            smol::block_on(app_env.virtual_lists.rows(request, &app_env))
+        else {
+            panic!("Expected the rows of the list");
+        };
+        assert_eq!(MAX_VIRTUAL_LIST_ROWS, rows.matches("<p>").count());
+
+        assert_eq!(None, ListRequest::from_ipc("puppeteer:vlist>numbers>5>1"));
+        assert!(smol::block_on(app_env.virtual_lists.rows(
+            ListRequest::from_ipc("puppeteer:vlist>missing>0>1").unwrap(),
+            &app_env
+        ))
+        .is_err());
+    }
+}